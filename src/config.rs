@@ -2,6 +2,7 @@ use crate::authentication::CredentialsStore;
 use crate::providers::units::Coordinates;
 use crate::providers::HttpRequestCache;
 use crate::providers::{Providers, WeatherProvider, WeatherRequest};
+use crate::stations::Station;
 use anyhow::{anyhow, Context};
 use const_format::concatcp;
 use figment::{
@@ -40,6 +41,8 @@ pub struct Config {
     pub locations: BTreeMap<String, Location>,
     #[serde(rename = "provider")]
     pub providers: Option<Providers>,
+    #[serde(rename = "station", default)]
+    pub stations: BTreeMap<String, Station>,
     pub http: rocket::Config,
     pub auth: Option<CredentialsStore>,
 }
@@ -57,6 +60,7 @@ impl Default for Config {
         Self {
             locations: BTreeMap::new(),
             providers: None,
+            stations: BTreeMap::new(),
             http: default_rocket_config(),
             auth: None,
         }
@@ -96,24 +100,69 @@ pub struct Task {
 }
 
 pub fn get_provider_tasks(config: Config) -> anyhow::Result<ProviderTasks> {
-    let configured_providers = config
-        .providers
-        .with_context(|| "No providers configured")?;
+    // Stations pushing their observations are an alternative to providers
+    let configured_providers = match config.providers {
+        Some(providers) => providers.create()?,
+        None if !config.stations.is_empty() => vec![],
+        None => return Err(anyhow!("No providers configured")),
+    };
 
     let mut tasks: ProviderTasks = vec![];
 
     for configured_provider in configured_providers {
-        let max_capacity = config
+        // Providers of local sensors only have weather for some of the locations
+        let locations: Vec<_> = config
             .locations
+            .iter()
+            .map(|(name, location)| WeatherRequest {
+                name: location.name.clone().unwrap_or_else(|| name.clone()),
+                query: location.coordinates.clone(),
+            })
+            .filter(|request| {
+                let served = configured_provider.serves(&request.name);
+                if !served {
+                    debug!(
+                        "Skipping location {} not served by {}",
+                        request.name,
+                        configured_provider.id()
+                    );
+                }
+                served
+            })
+            .collect();
+
+        if locations.is_empty() {
+            warn!(
+                "{} does not serve any of the configured locations",
+                configured_provider.id()
+            );
+        }
+
+        let max_capacity = locations
             .len()
+            .max(1)
             .checked_mul(configured_provider.cache_cardinality())
             .ok_or_else(|| anyhow!("Overflow while calculating max capacity"))?;
-        let cache = CacheBuilder::new(max_capacity.try_into()?)
-            .time_to_live(configured_provider.refresh_interval())
-            .build();
+        let mut cache_builder = CacheBuilder::new(max_capacity.try_into()?)
+            .time_to_live(configured_provider.refresh_interval());
+
+        // Named caches scope the circuit breakers, so a failing instance does not affect others
+        if let Some(instance) = configured_provider.instance() {
+            cache_builder = cache_builder.name(&format!("{}.{instance}", configured_provider.id()));
+        }
+
+        let cache = cache_builder.build();
 
         debug!("Found configured provider {configured_provider:?}");
 
+        configured_provider
+            .validate(locations.len())
+            .with_context(|| format!("Invalid configuration for {}", configured_provider.id()))?;
+
+        configured_provider
+            .start()
+            .with_context(|| format!("Could not start {}", configured_provider.id()))?;
+
         if configured_provider.refresh_interval() < Duration::from_secs(60 * 5) {
             warn!(
                 "Updating weather information more often than every 5 minutes is discouraged. Consider increasing the refresh interval for {}",
@@ -121,14 +170,10 @@ pub fn get_provider_tasks(config: Config) -> anyhow::Result<ProviderTasks> {
             );
         }
 
-        let locations = config.locations.clone();
-        for (name, location) in locations {
+        for request in locations {
             tasks.push(Task {
                 provider: Arc::clone(&configured_provider),
-                request: WeatherRequest {
-                    name: location.name.unwrap_or(name),
-                    query: location.coordinates,
-                },
+                request,
                 client: Client::new(),
                 cache: cache.clone(),
             });
@@ -1,8 +1,9 @@
 use crate::config::NAME;
 use log::{error, info, trace};
 use once_cell::sync::Lazy;
+use rocket::form::Form;
 use rocket::http::{Accept, ContentType, Header, MediaType, QMediaType, Status};
-use rocket::{get, routes, Build, Either, Responder, Rocket, State};
+use rocket::{get, post, routes, Build, Either, Responder, Rocket, State};
 use rocket_basicauth::BasicAuth;
 use std::cmp::Ordering;
 
@@ -13,6 +14,7 @@ use crate::authentication::{maybe_authenticate, CredentialsStore, Denied};
 use crate::error::exit_if_handle_fatal;
 use crate::prometheus::{format_metrics, Format};
 use crate::providers::Weather;
+use crate::stations::{Fields, Protocol, Rejection, Stations, ECOWITT, WUNDERGROUND};
 use rocket::tokio::task;
 use rocket::tokio::task::JoinSet;
 use tokio::task::JoinError;
@@ -24,15 +26,25 @@ pub async fn configure_rocket(config: Config) -> Rocket<Build> {
         .unwrap_or_else(exit_if_handle_fatal)
         .unwrap_or_else(exit_if_handle_fatal);
 
-    #[allow(clippy::no_effect_underscore_binding)]
+    #[allow(
+        clippy::no_effect_underscore_binding,
+        reason = "Bindings generated by the routes macro"
+    )]
     rocket::custom(config.http)
         .manage(tasks)
         .manage(config.auth)
-        .mount("/", routes![index, metrics])
+        .manage(Stations::new(config.stations))
+        .mount(
+            "/",
+            routes![index, metrics, ecowitt_upload, wunderground_upload],
+        )
 }
 
 #[get("/")]
-#[allow(clippy::needless_pass_by_value)]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Rocket passes request guards by value"
+)]
 fn index(
     credentials_store: &State<Option<CredentialsStore>>,
     credentials_presented: Option<BasicAuth>,
@@ -51,12 +63,13 @@ fn index(
 #[get("/metrics")]
 async fn metrics(
     unscheduled_tasks: &State<ProviderTasks>,
+    stations: &State<Stations>,
     credentials_store: &State<Option<CredentialsStore>>,
     credentials_presented: Option<BasicAuth>,
     accept: &Accept,
 ) -> Result<MetricsResponse, Either<UnauthorizedResponse, ForbiddenResponse>> {
     match maybe_authenticate(credentials_store.as_ref(), credentials_presented.as_ref()) {
-        Ok(_) => Ok(serve_metrics(get_metrics_format(accept), unscheduled_tasks).await),
+        Ok(_) => Ok(serve_metrics(get_metrics_format(accept), unscheduled_tasks, stations).await),
         Err(e) => auth_error_to_response(&e),
     }
 }
@@ -64,6 +77,7 @@ async fn metrics(
 async fn serve_metrics(
     format: Format,
     unscheduled_tasks: &State<ProviderTasks>,
+    stations: &State<Stations>,
 ) -> MetricsResponse {
     let mut join_set = JoinSet::new();
 
@@ -80,25 +94,26 @@ async fn serve_metrics(
         }));
     }
 
-    wait_for_metrics(format, join_set).await.map_or_else(
-        |e| {
-            error!("General error while fetching weather data: {e}");
-            MetricsResponse::new(
-                Status::InternalServerError,
-                format,
-                "Error while fetching weather data. Check the logs".into(),
-            )
-        },
-        |metrics| MetricsResponse::new(Status::Ok, format, metrics),
-    )
+    wait_for_metrics(format, join_set, stations.weather())
+        .await
+        .map_or_else(
+            |e| {
+                error!("General error while fetching weather data: {e}");
+                MetricsResponse::new(
+                    Status::InternalServerError,
+                    format,
+                    "Error while fetching weather data. Check the logs".into(),
+                )
+            },
+            |metrics| MetricsResponse::new(Status::Ok, format, metrics),
+        )
 }
 
 async fn wait_for_metrics(
     format: Format,
     mut join_set: JoinSet<Result<anyhow::Result<Weather>, JoinError>>,
+    mut weather: Vec<Weather>,
 ) -> anyhow::Result<String> {
-    let mut weather = vec![];
-
     while let Some(result) = join_set.join_next().await {
         result??.map_or_else(
             |e| error!("Provider error while fetching weather data: {e}"),
@@ -109,6 +124,36 @@ async fn wait_for_metrics(
     format_metrics(format, weather)
 }
 
+// Stations authenticate with their passkey or ID, so uploads are not subject to basic auth
+#[post("/data/report/<_..>", data = "<fields>")]
+fn ecowitt_upload(stations: &State<Stations>, fields: Form<Fields>) -> (Status, &'static str) {
+    receive_upload(stations, &ECOWITT, fields.into_inner())
+}
+
+#[get("/weatherstation/updateweatherstation.php?<fields..>")]
+#[allow(
+    clippy::needless_pass_by_value,
+    reason = "Rocket passes request guards by value"
+)]
+fn wunderground_upload(stations: &State<Stations>, fields: Fields) -> (Status, &'static str) {
+    receive_upload(stations, &WUNDERGROUND, fields)
+}
+
+fn receive_upload(
+    stations: &Stations,
+    protocol: &'static Protocol,
+    fields: Fields,
+) -> (Status, &'static str) {
+    match stations.receive(protocol, fields) {
+        Ok(()) => (Status::Ok, "success"),
+        Err(Rejection::UnknownStation) => (Status::Forbidden, "Unknown station"),
+        Err(Rejection::InvalidUpload(e)) => {
+            error!("Invalid upload: {e}");
+            (Status::BadRequest, "Invalid upload")
+        }
+    }
+}
+
 fn auth_error_to_response<T>(
     error: &Denied,
 ) -> Result<T, Either<UnauthorizedResponse, ForbiddenResponse>> {
@@ -525,4 +570,91 @@ mod tests {
             );
         }
     }
+
+    mod station_upload {
+        use crate::config::Config;
+        use crate::http_server::configure_rocket;
+        use rocket::http::{ContentType, Header, Status};
+        use rocket::local::asynchronous::Client;
+
+        async fn client() -> Client {
+            let config = Config {
+                stations: toml::from_str(
+                    r#"
+                    [garden]
+                    latitude = 52.52
+                    longitude = 13.405
+                    passkey = "34B4F2C4A9F1B0E1"
+
+                    [roof]
+                    latitude = 52.52
+                    longitude = 13.405
+                    id = "KBERLIN1"
+                    password = "secret"
+                    "#,
+                )
+                .expect("Valid configuration"),
+                ..Config::default()
+            };
+
+            Client::untracked(configure_rocket(config).await)
+                .await
+                .expect("Valid rocket instance")
+        }
+
+        #[rocket::async_test]
+        async fn ecowitt() {
+            let client = client().await;
+
+            let response = client
+                .post("/data/report/")
+                .header(ContentType::Form)
+                .body("PASSKEY=34B4F2C4A9F1B0E1&stationtype=GW1100A_V2.1.4&tempf=50.0&humidity=81")
+                .dispatch()
+                .await;
+            assert_eq!(response.status(), Status::Ok);
+
+            let metrics = client
+                .get("/metrics")
+                .header(Header::new("accept", "text/plain"))
+                .dispatch()
+                .await
+                .into_string()
+                .await
+                .expect("Metrics are served");
+            assert!(
+                metrics.contains(r#"source="local.station",location="garden""#),
+                "Station is served: {metrics}"
+            );
+            assert!(
+                metrics.contains("weather_relative_humidity_ratio"),
+                "Humidity is served: {metrics}"
+            );
+        }
+
+        #[rocket::async_test]
+        async fn wunderground() {
+            let client = client().await;
+
+            let response = client
+                .get("/weatherstation/updateweatherstation.php?ID=KBERLIN1&PASSWORD=secret&dateutc=now&tempf=50.0&action=updateraw")
+                .dispatch()
+                .await;
+
+            assert_eq!(response.status(), Status::Ok);
+            assert_eq!(response.into_string().await.as_deref(), Some("success"));
+        }
+
+        #[rocket::async_test]
+        async fn reject_unknown_station() {
+            let client = client().await;
+
+            let response = client
+                .get("/weatherstation/updateweatherstation.php?ID=KBERLIN1&PASSWORD=wrong&tempf=50.0")
+                .dispatch()
+                .await;
+
+            assert_eq!(response.status(), Status::Forbidden);
+        }
+    }
 }
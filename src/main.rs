@@ -13,6 +13,7 @@ mod http_server;
 mod logging;
 mod prometheus;
 mod providers;
+mod stations;
 
 #[cfg(debug_assertions)]
 #[derive(Copy, Clone, Debug, Default)]
@@ -1,11 +1,16 @@
 use crate::config::{NAME, VERSION};
-use crate::providers::Weather;
-use log::debug;
+use crate::providers::{Forecast, Measurement, Weather};
+use log::{debug, warn};
 use prometheus_client::encoding::text::encode;
-use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::encoding::{EncodeLabel, EncodeLabelSet, LabelSetEncoder};
 use prometheus_client::metrics::family::Family;
 use prometheus_client::metrics::gauge::Gauge;
 use prometheus_client::registry::{Registry, Unit};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::iter;
+use std::mem;
 use std::sync::atomic::AtomicU64;
 
 #[derive(PartialEq, Debug, Eq, Copy, Clone)]
@@ -22,6 +27,395 @@ struct Labels {
     city: String,
     latitude: String,
     longitude: String,
+    #[prometheus(flatten)]
+    optional: OptionalLabels,
+}
+
+/// Labels that only some weather sources have, which are left out entirely instead of being
+/// exported empty when missing
+#[derive(Clone, Hash, Eq, PartialEq, Debug, Default)]
+struct OptionalLabels {
+    instance: Option<String>,
+    model: Option<String>,
+    module: Option<String>,
+}
+
+impl EncodeLabelSet for OptionalLabels {
+    fn encode(&self, mut encoder: LabelSetEncoder) -> Result<(), fmt::Error> {
+        if let Some(instance) = &self.instance {
+            ("instance", instance.as_str()).encode(encoder.encode_label())?;
+        }
+
+        if let Some(model) = &self.model {
+            ("model", model.as_str()).encode(encoder.encode_label())?;
+        }
+
+        if let Some(module) = &self.module {
+            ("module", module.as_str()).encode(encoder.encode_label())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, EncodeLabelSet, Debug)]
+struct ConditionLabels {
+    condition: String,
+    // Flattening consumes the encoder, so it needs to come last
+    #[prometheus(flatten)]
+    labels: Labels,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, EncodeLabelSet, Debug)]
+struct AlertLabels {
+    event: String,
+    sender: String,
+    #[prometheus(flatten)]
+    labels: Labels,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq, EncodeLabelSet, Debug)]
+struct ForecastLabels {
+    hours_ahead: String,
+    #[prometheus(flatten)]
+    labels: Labels,
+}
+
+type WeatherGauge<L = Labels> = Family<L, Gauge<f64, AtomicU64>>;
+
+/// A gauge that is only registered once the first value is observed so that measurements no
+/// provider supports do not show up as empty metrics
+struct OptionalGauge<L = Labels> {
+    name: &'static str,
+    help: &'static str,
+    unit: Option<Unit>,
+    family: WeatherGauge<L>,
+    registered: bool,
+}
+
+impl<L: Clone + Hash + Eq + EncodeLabelSet + Debug + Send + Sync + 'static> OptionalGauge<L> {
+    fn new(name: &'static str, help: &'static str, unit: Unit) -> Self {
+        Self {
+            unit: Some(unit),
+            ..Self::without_unit(name, help)
+        }
+    }
+
+    fn without_unit(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            unit: None,
+            family: WeatherGauge::default(),
+            registered: false,
+        }
+    }
+
+    fn set<T: Into<f64>>(&mut self, registry: &mut Registry, labels: &L, maybe: Option<T>) {
+        if let Some(value) = maybe {
+            if !self.registered {
+                let help = format!("{NAME} {}", self.help);
+
+                match &self.unit {
+                    Some(unit) => registry.register_with_unit(
+                        self.name,
+                        help,
+                        unit.clone(),
+                        self.family.clone(),
+                    ),
+                    None => registry.register(self.name, help, self.family.clone()),
+                }
+                self.registered = true;
+            }
+
+            self.family.get_or_create(labels).set(value.into());
+        }
+    }
+}
+
+/// Gauges for the current weather measurements beyond temperature
+struct WeatherGauges {
+    humidity: OptionalGauge,
+    station_distance: OptionalGauge,
+    dew_point: OptionalGauge,
+    pressure: OptionalGauge,
+    wind_speed: OptionalGauge,
+    wind_gust: OptionalGauge,
+    wind_direction: OptionalGauge,
+    cloud_cover: OptionalGauge,
+    sunshine_duration: OptionalGauge,
+    precipitation: OptionalGauge,
+    precipitation_intensity: OptionalGauge,
+    visibility: OptionalGauge,
+    elevation: OptionalGauge,
+    uv_index: OptionalGauge,
+}
+
+impl WeatherGauges {
+    fn new() -> Self {
+        Self {
+            humidity: OptionalGauge::new(
+                "relative_humidity",
+                "relative humidity",
+                Unit::Other("ratio".into()),
+            ),
+            station_distance: OptionalGauge::new(
+                "station_distance",
+                "weather station distance in meters",
+                Unit::Meters,
+            ),
+            dew_point: OptionalGauge::new("dew_point", "dew point temperature", Unit::Celsius),
+            pressure: OptionalGauge::new(
+                "pressure",
+                "atmospheric pressure",
+                Unit::Other("hectopascals".into()),
+            ),
+            wind_speed: OptionalGauge::new(
+                "wind_speed",
+                "wind speed",
+                Unit::Other("meters_per_second".into()),
+            ),
+            wind_gust: OptionalGauge::new(
+                "wind_gust_speed",
+                "wind gust speed",
+                Unit::Other("meters_per_second".into()),
+            ),
+            wind_direction: OptionalGauge::new(
+                "wind_direction",
+                "wind direction",
+                Unit::Other("degrees".into()),
+            ),
+            cloud_cover: OptionalGauge::new(
+                "cloud_cover",
+                "cloud cover",
+                Unit::Other("ratio".into()),
+            ),
+            sunshine_duration: OptionalGauge::new(
+                "sunshine_duration",
+                "sunshine duration during the latest measurement interval",
+                Unit::Seconds,
+            ),
+            precipitation: OptionalGauge::new(
+                "precipitation",
+                "precipitation during the latest measurement interval",
+                Unit::Other("millimeters".into()),
+            ),
+            precipitation_intensity: OptionalGauge::new(
+                "precipitation_intensity",
+                "precipitation intensity",
+                Unit::Other("millimeters_per_hour".into()),
+            ),
+            visibility: OptionalGauge::new("visibility", "visibility in meters", Unit::Meters),
+            elevation: OptionalGauge::new(
+                "elevation",
+                "elevation of the weather location in meters",
+                Unit::Meters,
+            ),
+            uv_index: OptionalGauge::without_unit("uv_index", "UV index"),
+        }
+    }
+
+    fn set(&mut self, registry: &mut Registry, labels: &Labels, weather: &Weather) {
+        self.humidity
+            .set(registry, labels, weather.relative_humidity);
+        self.station_distance
+            .set(registry, labels, weather.distance.clone());
+        self.dew_point.set(registry, labels, weather.dew_point);
+        self.pressure.set(registry, labels, weather.pressure);
+        self.wind_speed.set(registry, labels, weather.wind_speed);
+        self.wind_gust.set(registry, labels, weather.wind_gust);
+        self.wind_direction
+            .set(registry, labels, weather.wind_direction);
+        self.cloud_cover.set(registry, labels, weather.cloud_cover);
+        self.sunshine_duration.set(
+            registry,
+            labels,
+            weather.sunshine_duration.map(|d| d.as_secs_f64()),
+        );
+        self.precipitation
+            .set(registry, labels, weather.precipitation);
+        self.precipitation_intensity
+            .set(registry, labels, weather.precipitation_intensity);
+        self.visibility
+            .set(registry, labels, weather.visibility.clone());
+        self.elevation
+            .set(registry, labels, weather.elevation.clone());
+        self.uv_index.set(registry, labels, weather.uv_index);
+    }
+}
+
+/// Gauges for forecast steps, labelled with how many hours ahead of the current weather they are
+struct ForecastGauges {
+    temperature: OptionalGauge<ForecastLabels>,
+    dew_point: OptionalGauge<ForecastLabels>,
+    pressure: OptionalGauge<ForecastLabels>,
+    wind_speed: OptionalGauge<ForecastLabels>,
+    wind_gust: OptionalGauge<ForecastLabels>,
+    wind_direction: OptionalGauge<ForecastLabels>,
+    cloud_cover: OptionalGauge<ForecastLabels>,
+    precipitation: OptionalGauge<ForecastLabels>,
+}
+
+impl ForecastGauges {
+    fn new() -> Self {
+        Self {
+            temperature: OptionalGauge::new(
+                "forecast_temperature",
+                "forecast temperature",
+                Unit::Celsius,
+            ),
+            dew_point: OptionalGauge::new(
+                "forecast_dew_point",
+                "forecast dew point temperature",
+                Unit::Celsius,
+            ),
+            pressure: OptionalGauge::new(
+                "forecast_pressure",
+                "forecast atmospheric pressure",
+                Unit::Other("hectopascals".into()),
+            ),
+            wind_speed: OptionalGauge::new(
+                "forecast_wind_speed",
+                "forecast wind speed",
+                Unit::Other("meters_per_second".into()),
+            ),
+            wind_gust: OptionalGauge::new(
+                "forecast_wind_gust_speed",
+                "forecast wind gust speed",
+                Unit::Other("meters_per_second".into()),
+            ),
+            wind_direction: OptionalGauge::new(
+                "forecast_wind_direction",
+                "forecast wind direction",
+                Unit::Other("degrees".into()),
+            ),
+            cloud_cover: OptionalGauge::new(
+                "forecast_cloud_cover",
+                "forecast cloud cover",
+                Unit::Other("ratio".into()),
+            ),
+            precipitation: OptionalGauge::new(
+                "forecast_precipitation",
+                "forecast precipitation during the hour before the forecast step",
+                Unit::Other("millimeters".into()),
+            ),
+        }
+    }
+
+    fn set(&mut self, registry: &mut Registry, labels: &Labels, forecast: &Forecast) {
+        let forecast_labels = ForecastLabels {
+            hours_ahead: forecast.hours_ahead.to_string(),
+            labels: labels.clone(),
+        };
+
+        self.temperature
+            .set(registry, &forecast_labels, forecast.temperature);
+        self.dew_point
+            .set(registry, &forecast_labels, forecast.dew_point);
+        self.pressure
+            .set(registry, &forecast_labels, forecast.pressure);
+        self.wind_speed
+            .set(registry, &forecast_labels, forecast.wind_speed);
+        self.wind_gust
+            .set(registry, &forecast_labels, forecast.wind_gust);
+        self.wind_direction
+            .set(registry, &forecast_labels, forecast.wind_direction);
+        self.cloud_cover
+            .set(registry, &forecast_labels, forecast.cloud_cover);
+        self.precipitation
+            .set(registry, &forecast_labels, forecast.precipitation);
+    }
+}
+
+/// Names and unit suffixes of the built-in metrics
+const BUILT_IN_METRICS: [(&str, Option<&str>); 25] = [
+    ("temperature", Some("celsius")),
+    ("relative_humidity", Some("ratio")),
+    ("station_distance", Some("meters")),
+    ("dew_point", Some("celsius")),
+    ("pressure", Some("hectopascals")),
+    ("wind_speed", Some("meters_per_second")),
+    ("wind_gust_speed", Some("meters_per_second")),
+    ("wind_direction", Some("degrees")),
+    ("cloud_cover", Some("ratio")),
+    ("sunshine_duration", Some("seconds")),
+    ("precipitation", Some("millimeters")),
+    ("precipitation_intensity", Some("millimeters_per_hour")),
+    ("visibility", Some("meters")),
+    ("elevation", Some("meters")),
+    ("uv_index", None),
+    ("forecast_temperature", Some("celsius")),
+    ("forecast_dew_point", Some("celsius")),
+    ("forecast_pressure", Some("hectopascals")),
+    ("forecast_wind_speed", Some("meters_per_second")),
+    ("forecast_wind_gust_speed", Some("meters_per_second")),
+    ("forecast_wind_direction", Some("degrees")),
+    ("forecast_cloud_cover", Some("ratio")),
+    ("forecast_precipitation", Some("millimeters")),
+    ("condition_info", None),
+    ("alert_info", None),
+];
+
+/// Name a metric is exported under, without the registry prefix
+fn metric_name(name: &str, unit: Option<&str>) -> String {
+    unit.map_or_else(|| name.into(), |suffix| format!("{name}_{suffix}"))
+}
+
+/// Measurements cannot use the name of a built-in metric, neither on their own nor combined with
+/// their unit
+pub fn is_reserved_metric_name(name: &str, unit: Option<&str>) -> bool {
+    let exported = metric_name(name, unit);
+
+    BUILT_IN_METRICS
+        .iter()
+        .any(|(built_in, suffix)| *built_in == name || metric_name(built_in, *suffix) == exported)
+}
+
+/// Gauges for measurements without a counterpart in the weather model, registered by name and unit
+/// as they show up. Measurements that would be exported under the name of another metric are
+/// skipped, as a metric family can only be registered once
+#[derive(Default)]
+struct MeasurementGauges {
+    families: HashMap<(String, Option<String>), WeatherGauge>,
+    names: HashSet<String>,
+}
+
+impl MeasurementGauges {
+    fn set(&mut self, registry: &mut Registry, labels: &Labels, measurement: &Measurement) {
+        let key = (measurement.name.clone(), measurement.unit.clone());
+
+        if !self.families.contains_key(&key) {
+            let name = metric_name(&measurement.name, measurement.unit.as_deref());
+            if is_reserved_metric_name(&measurement.name, measurement.unit.as_deref())
+                || !self.names.insert(name.clone())
+            {
+                warn!(
+                    "Skipping measurement {name} of {}, a metric of that name already exists",
+                    labels.source
+                );
+                return;
+            }
+
+            let family = WeatherGauge::default();
+            let help = format!("{NAME} {}", measurement.name.replace('_', " "));
+
+            match &measurement.unit {
+                Some(unit) => registry.register_with_unit(
+                    &measurement.name,
+                    help,
+                    Unit::Other(unit.clone()),
+                    family.clone(),
+                ),
+                None => registry.register(&measurement.name, help, family.clone()),
+            }
+
+            self.families.insert(key.clone(), family);
+        }
+
+        if let Some(family) = self.families.get(&key) {
+            family.get_or_create(labels).set(measurement.value);
+        }
+    }
 }
 
 pub fn format_metrics(_format: Format, weathers: Vec<Weather>) -> anyhow::Result<String> {
@@ -29,7 +423,7 @@ pub fn format_metrics(_format: Format, weathers: Vec<Weather>) -> anyhow::Result
 
     let mut registry = Registry::with_prefix("weather");
 
-    let temperature = Family::<Labels, Gauge<f64, AtomicU64>>::default();
+    let temperature = WeatherGauge::default();
     registry.register_with_unit(
         "temperature",
         format!("{NAME} temperature"),
@@ -37,55 +431,74 @@ pub fn format_metrics(_format: Format, weathers: Vec<Weather>) -> anyhow::Result
         temperature.clone(),
     );
 
-    let humidity = Family::<Labels, Gauge<f64, AtomicU64>>::default();
-    let mut humidity_registered = false;
+    let mut current = WeatherGauges::new();
+    let mut forecast = ForecastGauges::new();
+    let mut measurements = MeasurementGauges::default();
+    let mut condition = OptionalGauge::<ConditionLabels>::without_unit(
+        "condition_info",
+        "current weather condition",
+    );
+    let mut alert =
+        OptionalGauge::<AlertLabels>::without_unit("alert_info", "active weather alert");
 
-    let station_distance = Family::<Labels, Gauge<f64, AtomicU64>>::default();
-    let mut station_distance_registered = false;
+    let series = weathers.into_iter().flat_map(|mut weather| {
+        let modules = mem::take(&mut weather.modules);
+        iter::once(weather).chain(modules)
+    });
 
-    for weather in weathers {
+    for weather in series {
         let labels = Labels {
             version: VERSION.into(),
-            source: weather.source,
+            source: weather.source.clone(),
             location: weather.location.clone(),
-            city: weather.city.unwrap_or_else(String::new),
+            city: weather.city.clone().unwrap_or_default(),
             latitude: weather.coordinates.latitude.to_string(),
             longitude: weather.coordinates.longitude.to_string(),
+            optional: OptionalLabels {
+                instance: weather.instance.clone(),
+                model: weather.model.clone(),
+                module: weather.module.clone(),
+            },
         };
 
-        temperature
-            .get_or_create(&labels)
-            .set(weather.temperature.into());
-
-        weather.relative_humidity.map(|relative_humidity_ratio| {
-            if !humidity_registered {
-                registry.register_with_unit(
-                    "relative_humidity",
-                    format!("{NAME} relative humidity"),
-                    Unit::Other("ratio".into()),
-                    humidity.clone(),
-                );
-                humidity_registered = true;
-            }
-
-            humidity
+        if !weather.without_temperature {
+            temperature
                 .get_or_create(&labels)
-                .set(relative_humidity_ratio.into())
-        });
+                .set(weather.temperature.into());
+        }
 
-        weather.distance.map(|meters| {
-            if !station_distance_registered {
-                registry.register_with_unit(
-                    "station_distance",
-                    format!("{NAME} weather station distance in meters"),
-                    Unit::Meters,
-                    station_distance.clone(),
-                );
-                station_distance_registered = true;
-            }
+        current.set(&mut registry, &labels, &weather);
 
-            station_distance.get_or_create(&labels).set(meters.into())
-        });
+        for step in &weather.forecast {
+            forecast.set(&mut registry, &labels, step);
+        }
+
+        for measurement in &weather.measurements {
+            measurements.set(&mut registry, &labels, measurement);
+        }
+
+        if let Some(name) = weather.condition {
+            condition.set(
+                &mut registry,
+                &ConditionLabels {
+                    condition: name,
+                    labels: labels.clone(),
+                },
+                Some(1.0_f64),
+            );
+        }
+
+        for active in weather.alerts {
+            alert.set(
+                &mut registry,
+                &AlertLabels {
+                    event: active.event,
+                    sender: active.sender,
+                    labels: labels.clone(),
+                },
+                Some(1.0_f64),
+            );
+        }
     }
 
     let mut buffer = String::new();
@@ -98,10 +511,13 @@ pub fn format_metrics(_format: Format, weathers: Vec<Weather>) -> anyhow::Result
 #[cfg(test)]
 mod tests {
     use crate::config::VERSION;
-    use crate::prometheus::{format_metrics, Format};
+    use crate::prometheus::{format_metrics, is_reserved_metric_name, Format};
     use crate::providers::units::Ratio::Fraction;
-    use crate::providers::units::{Celsius, Coordinate, Coordinates, Meters, Ratio};
-    use crate::providers::Weather;
+    use crate::providers::units::{
+        Celsius, Coordinate, Coordinates, Degrees, Hectopascals, Meters, MetersPerSecond,
+        Millimeters, Ratio,
+    };
+    use crate::providers::{Alert, Forecast, Measurement, Weather};
     use pretty_assertions::assert_str_eq;
     use std::cmp::Ordering;
 
@@ -157,6 +573,7 @@ mod tests {
             temperature: Celsius::from(25.5),
             relative_humidity,
             distance,
+            ..Weather::default()
         }
     }
 
@@ -229,6 +646,7 @@ weather_station_distance_meters{{version="{VERSION}",source="org.example",locati
                     temperature: Celsius::from(25.5),
                     relative_humidity: Some(Fraction(0.55)),
                     distance: None,
+                    ..Weather::default()
                 },
                 Weather {
                     source: "com.example".into(),
@@ -241,6 +659,7 @@ weather_station_distance_meters{{version="{VERSION}",source="org.example",locati
                     temperature: Celsius::from(15.5),
                     relative_humidity: Some(Fraction(0.75)),
                     distance: None,
+                    ..Weather::default()
                 },
             ],
             &format!(
@@ -258,4 +677,282 @@ weather_relative_humidity_ratio{{version="{VERSION}",source="org.example",locati
             ),
         );
     }
+
+    #[test]
+    fn format_atmospheric_measurements() {
+        test_format_metrics(
+            Format::Prometheus,
+            vec![Weather {
+                dew_point: Some(Celsius::from(12.5)),
+                pressure: Some(Hectopascals::from(1013.2_f64)),
+                wind_speed: Some(MetersPerSecond::from(3.4_f64)),
+                wind_gust: Some(MetersPerSecond::from(7.1_f64)),
+                wind_direction: Some(Degrees::from(270.0_f64)),
+                cloud_cover: Some(Fraction(0.25_f64)),
+                ..create_weather(None, None)
+            }],
+            &format!(
+                r#"# HELP weather_temperature_celsius prometheus-weathermen temperature.
+# TYPE weather_temperature_celsius gauge
+# UNIT weather_temperature_celsius celsius
+weather_temperature_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 25.5
+# HELP weather_dew_point_celsius prometheus-weathermen dew point temperature.
+# TYPE weather_dew_point_celsius gauge
+# UNIT weather_dew_point_celsius celsius
+weather_dew_point_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 12.5
+# HELP weather_pressure_hectopascals prometheus-weathermen atmospheric pressure.
+# TYPE weather_pressure_hectopascals gauge
+# UNIT weather_pressure_hectopascals hectopascals
+weather_pressure_hectopascals{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 1013.2
+# HELP weather_wind_speed_meters_per_second prometheus-weathermen wind speed.
+# TYPE weather_wind_speed_meters_per_second gauge
+# UNIT weather_wind_speed_meters_per_second meters_per_second
+weather_wind_speed_meters_per_second{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 3.4
+# HELP weather_wind_gust_speed_meters_per_second prometheus-weathermen wind gust speed.
+# TYPE weather_wind_gust_speed_meters_per_second gauge
+# UNIT weather_wind_gust_speed_meters_per_second meters_per_second
+weather_wind_gust_speed_meters_per_second{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 7.1
+# HELP weather_wind_direction_degrees prometheus-weathermen wind direction.
+# TYPE weather_wind_direction_degrees gauge
+# UNIT weather_wind_direction_degrees degrees
+weather_wind_direction_degrees{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 270.0
+# HELP weather_cloud_cover_ratio prometheus-weathermen cloud cover.
+# TYPE weather_cloud_cover_ratio gauge
+# UNIT weather_cloud_cover_ratio ratio
+weather_cloud_cover_ratio{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 0.25
+# EOF"#
+            ),
+        );
+    }
+
+    #[test]
+    fn format_condition() {
+        test_format_metrics(
+            Format::Prometheus,
+            vec![Weather {
+                condition: Some("rain".into()),
+                ..create_weather(None, None)
+            }],
+            &format!(
+                r#"# HELP weather_temperature_celsius prometheus-weathermen temperature.
+# TYPE weather_temperature_celsius gauge
+# UNIT weather_temperature_celsius celsius
+weather_temperature_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 25.5
+# HELP weather_condition_info prometheus-weathermen current weather condition.
+# TYPE weather_condition_info gauge
+weather_condition_info{{condition="rain",version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 1.0
+# EOF"#
+            ),
+        );
+    }
+
+    #[test]
+    fn format_instance_and_model() {
+        test_format_metrics(
+            Format::Prometheus,
+            vec![Weather {
+                instance: Some("icon".into()),
+                model: Some("icon_d2".into()),
+                ..create_weather(None, None)
+            }],
+            &format!(
+                r#"# HELP weather_temperature_celsius prometheus-weathermen temperature.
+# TYPE weather_temperature_celsius gauge
+# UNIT weather_temperature_celsius celsius
+weather_temperature_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400",instance="icon",model="icon_d2"}} 25.5
+# EOF"#
+            ),
+        );
+    }
+
+    #[test]
+    fn format_modules() {
+        test_format_metrics(
+            Format::Prometheus,
+            vec![Weather {
+                module: Some("Indoor".into()),
+                modules: vec![Weather {
+                    module: Some("Outdoor".into()),
+                    temperature: Celsius::from(9.5),
+                    ..create_weather(None, None)
+                }],
+                ..create_weather(None, None)
+            }],
+            &format!(
+                r#"# HELP weather_temperature_celsius prometheus-weathermen temperature.
+# TYPE weather_temperature_celsius gauge
+# UNIT weather_temperature_celsius celsius
+weather_temperature_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400",module="Indoor"}} 25.5
+weather_temperature_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400",module="Outdoor"}} 9.5
+# EOF"#
+            ),
+        );
+    }
+
+    #[test]
+    fn format_measurement() {
+        test_format_metrics(
+            Format::Prometheus,
+            vec![Weather {
+                measurements: vec![Measurement {
+                    name: "shortwave_radiation".into(),
+                    unit: Some("watts_per_square_meter".into()),
+                    value: 212.0,
+                }],
+                ..create_weather(None, None)
+            }],
+            &format!(
+                r#"# HELP weather_temperature_celsius prometheus-weathermen temperature.
+# TYPE weather_temperature_celsius gauge
+# UNIT weather_temperature_celsius celsius
+weather_temperature_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 25.5
+# HELP weather_shortwave_radiation_watts_per_square_meter prometheus-weathermen shortwave radiation.
+# TYPE weather_shortwave_radiation_watts_per_square_meter gauge
+# UNIT weather_shortwave_radiation_watts_per_square_meter watts_per_square_meter
+weather_shortwave_radiation_watts_per_square_meter{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 212.0
+# EOF"#
+            ),
+        );
+    }
+
+    #[test]
+    fn skip_measurements_named_like_other_metrics() {
+        test_format_metrics(
+            Format::Prometheus,
+            vec![Weather {
+                measurements: vec![
+                    Measurement {
+                        name: "temperature".into(),
+                        unit: Some("celsius".into()),
+                        value: 12.0,
+                    },
+                    Measurement {
+                        name: "soil_moisture".into(),
+                        unit: Some("ratio".into()),
+                        value: 0.3,
+                    },
+                    Measurement {
+                        name: "soil_moisture_ratio".into(),
+                        unit: None,
+                        value: 0.4,
+                    },
+                ],
+                ..create_weather(None, None)
+            }],
+            &format!(
+                r#"# HELP weather_temperature_celsius prometheus-weathermen temperature.
+# TYPE weather_temperature_celsius gauge
+# UNIT weather_temperature_celsius celsius
+weather_temperature_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 25.5
+# HELP weather_soil_moisture_ratio prometheus-weathermen soil moisture.
+# TYPE weather_soil_moisture_ratio gauge
+# UNIT weather_soil_moisture_ratio ratio
+weather_soil_moisture_ratio{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 0.3
+# EOF"#
+            ),
+        );
+    }
+
+    #[test]
+    fn reserve_built_in_metric_names() {
+        assert!(is_reserved_metric_name("temperature", Some("celsius")));
+        assert!(is_reserved_metric_name("temperature", None));
+        assert!(is_reserved_metric_name(
+            "wind_gust",
+            Some("speed_meters_per_second")
+        ));
+        assert!(!is_reserved_metric_name(
+            "soil_temperature",
+            Some("celsius")
+        ));
+    }
+
+    #[test]
+    fn format_alert() {
+        test_format_metrics(
+            Format::Prometheus,
+            vec![Weather {
+                alerts: vec![Alert {
+                    sender: "Weather Service".into(),
+                    event: "Wind Advisory".into(),
+                }],
+                ..create_weather(None, None)
+            }],
+            &format!(
+                r#"# HELP weather_temperature_celsius prometheus-weathermen temperature.
+# TYPE weather_temperature_celsius gauge
+# UNIT weather_temperature_celsius celsius
+weather_temperature_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 25.5
+# HELP weather_alert_info prometheus-weathermen active weather alert.
+# TYPE weather_alert_info gauge
+weather_alert_info{{event="Wind Advisory",sender="Weather Service",version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 1.0
+# EOF"#
+            ),
+        );
+    }
+
+    #[test]
+    fn format_forecast() {
+        test_format_metrics(
+            Format::Prometheus,
+            vec![Weather {
+                forecast: vec![
+                    Forecast {
+                        hours_ahead: 1,
+                        temperature: Some(Celsius::from(26.5)),
+                        ..Forecast::default()
+                    },
+                    Forecast {
+                        hours_ahead: 2,
+                        temperature: Some(Celsius::from(27.5)),
+                        precipitation: Some(Millimeters::from(0.4_f64)),
+                        ..Forecast::default()
+                    },
+                ],
+                ..create_weather(None, None)
+            }],
+            &format!(
+                r#"# HELP weather_temperature_celsius prometheus-weathermen temperature.
+# TYPE weather_temperature_celsius gauge
+# UNIT weather_temperature_celsius celsius
+weather_temperature_celsius{{version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 25.5
+# HELP weather_forecast_temperature_celsius prometheus-weathermen forecast temperature.
+# TYPE weather_forecast_temperature_celsius gauge
+# UNIT weather_forecast_temperature_celsius celsius
+weather_forecast_temperature_celsius{{hours_ahead="1",version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 26.5
+weather_forecast_temperature_celsius{{hours_ahead="2",version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 27.5
+# HELP weather_forecast_precipitation_millimeters prometheus-weathermen forecast precipitation during the hour before the forecast step.
+# TYPE weather_forecast_precipitation_millimeters gauge
+# UNIT weather_forecast_precipitation_millimeters millimeters
+weather_forecast_precipitation_millimeters{{hours_ahead="2",version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 0.4
+# EOF"#
+            ),
+        );
+    }
+
+    #[test]
+    fn format_forecast_only() {
+        test_format_metrics(
+            Format::Prometheus,
+            vec![Weather {
+                forecast: vec![Forecast {
+                    hours_ahead: 0,
+                    temperature: Some(Celsius::from(26.5)),
+                    ..Forecast::default()
+                }],
+                without_temperature: true,
+                ..create_weather(None, None)
+            }],
+            &format!(
+                r#"# HELP weather_temperature_celsius prometheus-weathermen temperature.
+# TYPE weather_temperature_celsius gauge
+# UNIT weather_temperature_celsius celsius
+# HELP weather_forecast_temperature_celsius prometheus-weathermen forecast temperature.
+# TYPE weather_forecast_temperature_celsius gauge
+# UNIT weather_forecast_temperature_celsius celsius
+weather_forecast_temperature_celsius{{hours_ahead="0",version="{VERSION}",source="org.example",location="My Name",city="Some City",latitude="20.1000000",longitude="10.0123400"}} 26.5
+# EOF"#
+            ),
+        );
+    }
 }
@@ -0,0 +1,369 @@
+use crate::providers::http_request::{request_cached, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, Hectopascals, KilometersPerHour, Ratio,
+    ToMetersPerSecond,
+};
+use crate::providers::{
+    calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::{anyhow, Context};
+use humantime_serde::re::humantime::format_duration;
+use moka::sync::Cache;
+use once_cell::sync::OnceCell;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SOURCE_URI: &str = "com.accuweather";
+const LOCATION_SEARCH_URL: &str =
+    "https://dataservice.accuweather.com/locations/v1/cities/geoposition/search";
+const CURRENT_CONDITIONS_URL: &str = "https://dataservice.accuweather.com/currentconditions/v1";
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccuWeather {
+    api_key: String,
+    #[serde(default = "default_refresh_interval")]
+    #[serde(with = "humantime_serde")]
+    refresh_interval: Duration,
+    #[serde(default = "default_location_refresh_interval")]
+    #[serde(with = "humantime_serde")]
+    location_refresh_interval: Duration,
+    #[serde(default = "default_daily_request_quota")]
+    daily_request_quota: u64,
+    #[serde(skip)]
+    location_cache: OnceCell<HttpRequestCache>,
+}
+
+// The free tier only allows 50 requests per day, so the usual 10 minutes would exhaust it quickly
+const fn default_refresh_interval() -> Duration {
+    Duration::from_secs(60 * 60)
+}
+
+// Location keys are stable, so looking them up once a week is plenty
+const fn default_location_refresh_interval() -> Duration {
+    Duration::from_secs(SECONDS_PER_DAY * 7)
+}
+
+const fn default_daily_request_quota() -> u64 {
+    50
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AccuWeatherLocation {
+    key: String,
+    localized_name: String,
+    geo_position: AccuWeatherGeoPosition,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AccuWeatherGeoPosition {
+    latitude: Coordinate,
+    longitude: Coordinate,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AccuWeatherCurrentConditions {
+    temperature: AccuWeatherMeasurement<Celsius>,
+    relative_humidity: Option<f64>,
+    dew_point: Option<AccuWeatherMeasurement<Celsius>>,
+    wind: Option<AccuWeatherWind>,
+    wind_gust: Option<AccuWeatherWindGust>,
+    pressure: Option<AccuWeatherMeasurement<Hectopascals>>,
+    cloud_cover: Option<f64>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AccuWeatherMeasurement<T> {
+    metric: AccuWeatherValue<T>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AccuWeatherValue<T> {
+    value: T,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AccuWeatherWind {
+    direction: AccuWeatherWindDirection,
+    speed: AccuWeatherMeasurement<KilometersPerHour>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AccuWeatherWindDirection {
+    degrees: Degrees,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct AccuWeatherWindGust {
+    speed: AccuWeatherMeasurement<KilometersPerHour>,
+}
+
+fn requests_per_day(interval: Duration) -> anyhow::Result<u64> {
+    match interval.as_secs() {
+        0 => Err(anyhow!("Interval must be at least one second")),
+        secs => Ok(SECONDS_PER_DAY.div_ceil(secs)),
+    }
+}
+
+impl AccuWeather {
+    /// Location lookups outlive the weather cache, so they are kept separately. The quota allows at
+    /// most one location per daily request
+    fn location_cache(&self) -> &HttpRequestCache {
+        self.location_cache.get_or_init(|| {
+            Cache::builder()
+                .max_capacity(self.daily_request_quota)
+                .time_to_live(self.location_refresh_interval)
+                .build()
+        })
+    }
+
+    fn daily_requests(&self, location_count: usize) -> anyhow::Result<u64> {
+        let per_location = requests_per_day(self.refresh_interval)?
+            .checked_add(requests_per_day(self.location_refresh_interval)?)
+            .ok_or_else(|| anyhow!("Overflow while calculating daily requests"))?;
+
+        u64::try_from(location_count)?
+            .checked_mul(per_location)
+            .ok_or_else(|| anyhow!("Overflow while calculating daily requests"))
+    }
+}
+
+impl RegisteredProvider for AccuWeather {
+    const KEY: &'static str = "accuweather";
+}
+
+impl WeatherProvider for AccuWeather {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let location_url = Url::parse_with_params(
+            LOCATION_SEARCH_URL,
+            &[
+                ("apikey", self.api_key.clone()),
+                (
+                    "q",
+                    format!("{},{}", request.query.latitude, request.query.longitude),
+                ),
+            ],
+        )?;
+
+        let location: AccuWeatherLocation = request_cached(&HttpCacheRequest::new_json_request(
+            SOURCE_URI,
+            client,
+            self.location_cache(),
+            &Method::GET,
+            &location_url,
+        ))?;
+
+        let conditions_url = Url::parse_with_params(
+            &format!("{CURRENT_CONDITIONS_URL}/{}", location.key),
+            &[("apikey", self.api_key.clone()), ("details", "true".into())],
+        )?;
+
+        let conditions: Vec<AccuWeatherCurrentConditions> =
+            request_cached(&HttpCacheRequest::new_json_request(
+                SOURCE_URI,
+                client,
+                cache,
+                &Method::GET,
+                &conditions_url,
+            ))?;
+
+        let current = conditions
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Empty current conditions list"))?;
+
+        let coordinates = Coordinates {
+            latitude: location.geo_position.latitude,
+            longitude: location.geo_position.longitude,
+        };
+        let distance = calculate_distance(&request.query, &coordinates);
+
+        Ok(Weather {
+            location: request.name.clone(),
+            source: SOURCE_URI.into(),
+            city: Some(location.localized_name),
+            coordinates,
+            distance: Some(distance),
+            temperature: current.temperature.metric.value,
+            relative_humidity: current.relative_humidity.map(Ratio::Percentage),
+            dew_point: current.dew_point.map(|v| v.metric.value),
+            pressure: current.pressure.map(|v| v.metric.value),
+            wind_speed: current
+                .wind
+                .as_ref()
+                .map(|v| v.speed.metric.value.to_meters_per_second()),
+            wind_gust: current
+                .wind_gust
+                .map(|v| v.speed.metric.value.to_meters_per_second()),
+            wind_direction: current.wind.map(|v| v.direction.degrees),
+            cloud_cover: current.cloud_cover.map(Ratio::Percentage),
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.refresh_interval
+    }
+
+    fn validate(&self, location_count: usize) -> anyhow::Result<()> {
+        let daily_requests = self
+            .daily_requests(location_count)
+            .context("Could not calculate daily requests")?;
+
+        if daily_requests > self.daily_request_quota {
+            return Err(anyhow!(
+                "Refreshing {location_count} location(s) every {} requires up to {daily_requests} requests per day, exceeding the daily quota of {}. Increase refresh_interval",
+                format_duration(self.refresh_interval),
+                self.daily_request_quota
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod validate {
+        use crate::providers::accuweather::AccuWeather;
+        use crate::providers::WeatherProvider;
+        use once_cell::sync::OnceCell;
+        use std::time::Duration;
+
+        fn accuweather(refresh_interval: Duration) -> AccuWeather {
+            AccuWeather {
+                api_key: "secret".into(),
+                refresh_interval,
+                location_refresh_interval: Duration::from_secs(60 * 60 * 24 * 7),
+                daily_request_quota: 50,
+                location_cache: OnceCell::new(),
+            }
+        }
+
+        #[test]
+        fn accept_interval_within_quota() {
+            assert!(
+                accuweather(Duration::from_secs(60 * 60))
+                    .validate(2)
+                    .is_ok(),
+                "Two locations hourly need 50 requests"
+            );
+        }
+
+        #[test]
+        fn reject_interval_exceeding_quota() {
+            assert!(accuweather(Duration::from_secs(60 * 60))
+                .validate(3)
+                .expect_err("Three locations hourly need 75 requests")
+                .to_string()
+                .contains("requires up to 75 requests per day, exceeding the daily quota of 50"));
+        }
+
+        #[test]
+        fn reject_zero_interval() {
+            assert!(
+                accuweather(Duration::from_secs(0)).validate(1).is_err(),
+                "Zero interval cannot be satisfied"
+            );
+        }
+    }
+
+    mod parse_response {
+        use crate::providers::accuweather::{AccuWeatherCurrentConditions, AccuWeatherLocation};
+        use crate::providers::units::{
+            Celsius, Degrees, Hectopascals, KilometersPerHour, ToMetersPerSecond,
+        };
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_location() {
+            let location: AccuWeatherLocation = serde_json::from_str(
+                r#"{
+                    "Version": 1,
+                    "Key": "178087",
+                    "Type": "City",
+                    "LocalizedName": "Berlin",
+                    "GeoPosition": {
+                        "Latitude": 52.517,
+                        "Longitude": 13.389,
+                        "Elevation": {"Metric": {"Value": 43.0, "Unit": "m", "UnitType": 5}}
+                    }
+                }"#,
+            )
+            .expect("Parsing works");
+
+            assert_eq!(location.key, "178087");
+            assert_eq!(location.localized_name, "Berlin");
+            assert_eq!(location.geo_position.latitude, 52.517_f64.into());
+            assert_eq!(location.geo_position.longitude, 13.389_f64.into());
+        }
+
+        #[test]
+        fn parse_current_conditions() {
+            let conditions: Vec<AccuWeatherCurrentConditions> = serde_json::from_str(
+                r#"[{
+                    "LocalObservationDateTime": "2026-10-18T10:00:00+02:00",
+                    "WeatherText": "Cloudy",
+                    "Temperature": {
+                        "Metric": {"Value": 11.1, "Unit": "C", "UnitType": 17},
+                        "Imperial": {"Value": 52.0, "Unit": "F", "UnitType": 18}
+                    },
+                    "RelativeHumidity": 82,
+                    "DewPoint": {"Metric": {"Value": 8.1, "Unit": "C", "UnitType": 17}},
+                    "Wind": {
+                        "Direction": {"Degrees": 248, "Localized": "WSW", "English": "WSW"},
+                        "Speed": {"Metric": {"Value": 18.0, "Unit": "km/h", "UnitType": 7}}
+                    },
+                    "WindGust": {"Speed": {"Metric": {"Value": 36.0, "Unit": "km/h", "UnitType": 7}}},
+                    "CloudCover": 95,
+                    "Pressure": {"Metric": {"Value": 1011.0, "Unit": "mb", "UnitType": 14}}
+                }]"#,
+            )
+            .expect("Parsing works");
+
+            let current = conditions.first().expect("One element");
+            assert_eq!(current.temperature.metric.value, Celsius::from(11.1));
+            assert_eq!(current.relative_humidity, Some(82_f64));
+            assert_eq!(
+                current.dew_point.as_ref().map(|v| v.metric.value),
+                Some(Celsius::from(8.1))
+            );
+            assert_eq!(
+                current.wind.as_ref().map(|v| v.direction.degrees),
+                Some(Degrees::from(248_f64))
+            );
+            assert_eq!(
+                current
+                    .wind_gust
+                    .as_ref()
+                    .map(|v| v.speed.metric.value.to_meters_per_second()),
+                Some(KilometersPerHour::from(36_f64).to_meters_per_second())
+            );
+            assert_eq!(
+                current.pressure.as_ref().map(|v| v.metric.value),
+                Some(Hectopascals::from(1011_f64))
+            );
+            assert_eq!(current.cloud_cover, Some(95_f64));
+        }
+    }
+}
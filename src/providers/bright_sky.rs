@@ -0,0 +1,233 @@
+use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, Hectopascals, KilometersPerHour, Meters, Ratio,
+    ToMetersPerSecond,
+};
+use crate::providers::{HttpRequestCache, Weather, WeatherProvider, WeatherRequest};
+use anyhow::anyhow;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SOURCE_URI: &str = "dev.brightsky";
+const DEFAULT_BASE_URL: &str = "https://api.brightsky.dev";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BrightSky {
+    #[serde(default = "default_base_url")]
+    base_url: String,
+    #[serde(flatten)]
+    cache: Configuration,
+}
+
+fn default_base_url() -> String {
+    DEFAULT_BASE_URL.into()
+}
+
+#[derive(Deserialize, Debug)]
+struct BrightSkyResponse {
+    weather: BrightSkyWeather,
+    sources: Vec<BrightSkySource>,
+}
+
+// Units follow the default "dwd" unit system of Bright Sky
+#[derive(Deserialize, Debug)]
+struct BrightSkyWeather {
+    source_id: u64,
+    temperature: Celsius,
+    relative_humidity: Option<f64>,
+    dew_point: Option<Celsius>,
+    pressure_msl: Option<Hectopascals>,
+    #[serde(rename = "wind_speed_10")]
+    wind_speed: Option<KilometersPerHour>,
+    #[serde(rename = "wind_gust_speed_10")]
+    wind_gust: Option<KilometersPerHour>,
+    #[serde(rename = "wind_direction_10")]
+    wind_direction: Option<Degrees>,
+    cloud_cover: Option<f64>,
+    condition: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct BrightSkySource {
+    id: u64,
+    station_name: String,
+    #[serde(rename = "lat")]
+    latitude: Coordinate,
+    #[serde(rename = "lon")]
+    longitude: Coordinate,
+    distance: f64,
+}
+
+fn current_weather_url(base_url: &str, coordinates: &Coordinates) -> anyhow::Result<Url> {
+    let mut url = Url::parse(base_url)?;
+
+    url.path_segments_mut()
+        .map_err(|()| anyhow!("Base URL {base_url} cannot be used as a base"))?
+        .pop_if_empty()
+        .push("current_weather");
+
+    url.query_pairs_mut()
+        .append_pair("lat", &coordinates.latitude.to_string())
+        .append_pair("lon", &coordinates.longitude.to_string());
+
+    Ok(url)
+}
+
+impl RegisteredProvider for BrightSky {
+    const KEY: &'static str = "bright_sky";
+}
+
+impl WeatherProvider for BrightSky {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let url = current_weather_url(&self.base_url, &request.query)?;
+
+        let response: BrightSkyResponse = request_cached(&HttpCacheRequest::new_json_request(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &url,
+        ))?;
+
+        let current = response.weather;
+        let source = response
+            .sources
+            .into_iter()
+            .find(|source| source.id == current.source_id)
+            .ok_or_else(|| anyhow!("Missing source {} in response", current.source_id))?;
+
+        Ok(Weather {
+            location: request.name.clone(),
+            source: SOURCE_URI.into(),
+            city: Some(source.station_name),
+            coordinates: Coordinates {
+                latitude: source.latitude,
+                longitude: source.longitude,
+            },
+            distance: Some(Meters::from(source.distance)),
+            temperature: current.temperature,
+            relative_humidity: current.relative_humidity.map(Ratio::Percentage),
+            dew_point: current.dew_point,
+            pressure: current.pressure_msl,
+            wind_speed: current.wind_speed.map(|v| v.to_meters_per_second()),
+            wind_gust: current.wind_gust.map(|v| v.to_meters_per_second()),
+            wind_direction: current.wind_direction,
+            cloud_cover: current.cloud_cover.map(Ratio::Percentage),
+            condition: current.condition,
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod current_weather_url {
+        use crate::providers::bright_sky::{current_weather_url, DEFAULT_BASE_URL};
+        use crate::providers::units::Coordinates;
+        use pretty_assertions::assert_eq;
+
+        fn coordinates() -> Coordinates {
+            Coordinates {
+                latitude: 52.52_f64.into(),
+                longitude: 13.4_f64.into(),
+            }
+        }
+
+        #[test]
+        fn default_base_url() {
+            assert_eq!(
+                current_weather_url(DEFAULT_BASE_URL, &coordinates())
+                    .expect("Valid URL")
+                    .as_str(),
+                "https://api.brightsky.dev/current_weather?lat=52.5200000&lon=13.4000000"
+            );
+        }
+
+        #[test]
+        fn self_hosted_base_url() {
+            assert_eq!(
+                current_weather_url("http://localhost:5000/brightsky/", &coordinates())
+                    .expect("Valid URL")
+                    .as_str(),
+                "http://localhost:5000/brightsky/current_weather?lat=52.5200000&lon=13.4000000"
+            );
+        }
+    }
+
+    mod parse_response {
+        use crate::providers::bright_sky::BrightSkyResponse;
+        use crate::providers::units::{Celsius, Hectopascals};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_current_weather() {
+            let response: BrightSkyResponse = serde_json::from_str(
+                r#"{
+                    "weather": {
+                        "source_id": 6007,
+                        "timestamp": "2026-10-18T10:00:00+00:00",
+                        "cloud_cover": 88,
+                        "condition": "dry",
+                        "dew_point": 8.2,
+                        "precipitation_10": 0.0,
+                        "pressure_msl": 1016.3,
+                        "relative_humidity": 81,
+                        "temperature": 11.4,
+                        "visibility": 31000,
+                        "wind_direction_10": 240,
+                        "wind_speed_10": 14.4,
+                        "wind_gust_speed_10": 25.2,
+                        "icon": "cloudy",
+                        "fallback_source_ids": {}
+                    },
+                    "sources": [{
+                        "id": 6007,
+                        "dwd_station_id": "00433",
+                        "observation_type": "synop",
+                        "lat": 52.4676,
+                        "lon": 13.4021,
+                        "height": 48.0,
+                        "station_name": "Berlin-Tempelhof",
+                        "wmo_station_id": "10384",
+                        "first_record": "2026-10-17T10:30:00+00:00",
+                        "last_record": "2026-10-18T10:00:00+00:00",
+                        "distance": 5832.0
+                    }]
+                }"#,
+            )
+            .expect("Parsing works");
+
+            let source = response.sources.first().expect("One source");
+            assert_eq!(source.id, response.weather.source_id);
+            assert_eq!(source.station_name, "Berlin-Tempelhof");
+            assert!(
+                (source.distance - 5832_f64).abs() < f64::EPSILON,
+                "Distance in meters"
+            );
+            assert_eq!(response.weather.temperature, Celsius::from(11.4));
+            assert_eq!(response.weather.relative_humidity, Some(81_f64));
+            assert_eq!(
+                response.weather.pressure_msl,
+                Some(Hectopascals::from(1016.3_f64))
+            );
+            assert_eq!(response.weather.cloud_cover, Some(88_f64));
+            assert_eq!(response.weather.condition.as_deref(), Some("dry"));
+        }
+    }
+}
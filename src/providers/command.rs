@@ -0,0 +1,433 @@
+use crate::providers::fields::{field_weather, pointer_value, Field};
+use crate::providers::http_request::Configuration;
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::Coordinates;
+use crate::providers::{HttpRequestCache, Weather, WeatherProvider, WeatherRequest};
+use anyhow::{anyhow, Context};
+use log::debug;
+use moka::sync::Cache;
+use once_cell::sync::OnceCell;
+use reqwest::blocking::Client;
+use serde::de::value::Error as DeserializeError;
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{self, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SOURCE_URI: &str = "local.command";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Command {
+    location: String,
+    #[serde(flatten)]
+    input: Input,
+    #[serde(default = "default_timeout")]
+    #[serde(with = "humantime_serde")]
+    timeout: Duration,
+    #[serde(default = "default_max_output_size")]
+    max_output_size: u64,
+    #[serde(flatten)]
+    cache: Configuration,
+    #[serde(skip)]
+    output_cache: OnceCell<Cache<String, Vec<u8>>>,
+}
+
+/// Where readings come from, either the output of an executable or the contents of a file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum Input {
+    Command { command: Vec<String> },
+    File { file: PathBuf },
+}
+
+const fn default_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+const fn default_max_output_size() -> u64 {
+    64 * 1024
+}
+
+fn read_limited(reader: impl Read, max_output_size: u64) -> anyhow::Result<Vec<u8>> {
+    let mut output = vec![];
+    reader
+        .take(max_output_size.saturating_add(1))
+        .read_to_end(&mut output)?;
+
+    if u64::try_from(output.len())? > max_output_size {
+        return Err(anyhow!("Output exceeds {max_output_size} bytes"));
+    }
+
+    Ok(output)
+}
+
+/// Run a command and return its standard output, killing it if it does not exit in time
+fn run_command(
+    command: &[String],
+    timeout: Duration,
+    max_output_size: u64,
+) -> anyhow::Result<Vec<u8>> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| anyhow!("Command is empty"))?;
+
+    let mut child = process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Could not run {program}"))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("Output of {program} is not captured"))?;
+    // Processes started by the command may keep the output open after it exited, so reading it is
+    // bounded by the timeout as well
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || sender.send(read_limited(stdout, max_output_size)).ok());
+
+    let deadline = Instant::now()
+        .checked_add(timeout)
+        .ok_or_else(|| anyhow!("Timeout {timeout:?} is too large"))?;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Err(anyhow!("{program} did not exit within {timeout:?}"));
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let output = receiver
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .map_err(|e| anyhow!("Output of {program} was not closed within {timeout:?}: {e}"))?
+        .with_context(|| format!("Could not read the output of {program}"))?;
+
+    if !status.success() {
+        return Err(anyhow!("{program} failed with {status}"));
+    }
+
+    Ok(output)
+}
+
+/// Field a key is named after, `None` for unknown keys
+fn key_field(key: &str) -> Option<Field> {
+    Field::deserialize(key.into_deserializer())
+        .map_err(|e: DeserializeError| debug!("Ignoring key {key}: {e}"))
+        .ok()
+}
+
+/// Parse a JSON object or `key=value` lines, with keys named like weather fields. Unknown keys are
+/// ignored
+fn parse_output(output: &str) -> anyhow::Result<BTreeMap<Field, f64>> {
+    if output.trim_start().starts_with('{') {
+        return serde_json::from_str::<BTreeMap<String, Value>>(output)?
+            .into_iter()
+            .filter_map(|(key, value)| key_field(&key).map(|field| (field, value)))
+            .map(|(field, value)| {
+                pointer_value(&value, "")
+                    .map(|number| (field, number))
+                    .with_context(|| format!("Invalid value of {field:?}"))
+            })
+            .collect();
+    }
+
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| match line.split_once('=') {
+            Some((key, raw_value)) => key_field(key.trim()).map(|field| Ok((field, raw_value))),
+            None => Some(Err(anyhow!("Invalid line {line:?}"))),
+        })
+        .map(|entry| {
+            let (field, raw_value) = entry?;
+            let value = raw_value
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid value of {field:?}"))?;
+            Ok((field, value))
+        })
+        .collect()
+}
+
+impl Command {
+    /// The output is cached by the provider itself, as it is not requested over HTTP
+    fn output_cache(&self) -> &Cache<String, Vec<u8>> {
+        self.output_cache.get_or_init(|| {
+            Cache::builder()
+                .max_capacity(1)
+                .time_to_live(self.cache.refresh_interval)
+                .build()
+        })
+    }
+
+    fn output(&self) -> anyhow::Result<Vec<u8>> {
+        match &self.input {
+            Input::Command { command } => run_command(command, self.timeout, self.max_output_size),
+            Input::File { file } => read_limited(
+                File::open(file).with_context(|| format!("Could not open {}", file.display()))?,
+                self.max_output_size,
+            ),
+        }
+    }
+}
+
+impl RegisteredProvider for Command {
+    const KEY: &'static str = "command";
+}
+
+impl WeatherProvider for Command {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        _client: &Client,
+        _cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        if request.name != self.location {
+            return Err(anyhow!(
+                "Command provider only serves {}, not {}",
+                self.location,
+                request.name
+            ));
+        }
+
+        let output = self
+            .output_cache()
+            .try_get_with(self.location.clone(), || self.output())
+            .map_err(|e| anyhow!(e))?;
+
+        field_weather(
+            SOURCE_URI,
+            request,
+            &parse_output(&String::from_utf8(output)?)?,
+        )
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+
+    fn serves(&self, location: &str) -> bool {
+        self.location == location
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse_output {
+        use crate::providers::command::parse_output;
+        use crate::providers::fields::Field;
+        use pretty_assertions::assert_eq;
+        use std::collections::BTreeMap;
+
+        #[test]
+        fn parse_json() {
+            assert_eq!(
+                parse_output(r#"{"temperature": 21.5, "relative_humidity": "48", "gas": 12000}"#)
+                    .expect("Parsing works"),
+                BTreeMap::from([
+                    (Field::Temperature, 21.5_f64),
+                    (Field::RelativeHumidity, 48.0_f64)
+                ])
+            );
+        }
+
+        #[test]
+        fn parse_key_value_lines() {
+            assert_eq!(
+                parse_output("# BME280\ntemperature=21.5\n\npressure = 1013.2\ngas=12000\n")
+                    .expect("Parsing works"),
+                BTreeMap::from([
+                    (Field::Temperature, 21.5_f64),
+                    (Field::Pressure, 1013.2_f64)
+                ])
+            );
+        }
+
+        #[test]
+        fn ignore_non_numeric_unknown_keys() {
+            assert_eq!(
+                parse_output(
+                    r#"{"temperature": 21.5, "sensor": "BME280", "time": "2026-10-18T10:00:00Z"}"#
+                )
+                .expect("Parsing works"),
+                BTreeMap::from([(Field::Temperature, 21.5_f64)])
+            );
+            assert_eq!(
+                parse_output("status=ok\ntemperature=21.5\n").expect("Parsing works"),
+                BTreeMap::from([(Field::Temperature, 21.5_f64)])
+            );
+        }
+
+        #[test]
+        fn fail_on_invalid_value() {
+            let error = parse_output("temperature=warm").expect_err("Parsing fails");
+
+            assert_eq!(error.to_string(), "Invalid value of Temperature");
+        }
+
+        #[test]
+        fn fail_on_invalid_line() {
+            let error = parse_output("temperature: 21.5").expect_err("Parsing fails");
+
+            assert_eq!(error.to_string(), r#"Invalid line "temperature: 21.5""#);
+        }
+    }
+
+    mod run_command {
+        use crate::providers::command::run_command;
+        use pretty_assertions::assert_eq;
+        use std::time::Duration;
+
+        fn shell(script: &str) -> Vec<String> {
+            vec!["sh".into(), "-c".into(), script.into()]
+        }
+
+        #[test]
+        fn capture_output() {
+            assert_eq!(
+                run_command(
+                    &shell("echo temperature=21.5"),
+                    Duration::from_secs(5),
+                    1024
+                )
+                .expect("Command runs"),
+                b"temperature=21.5\n"
+            );
+        }
+
+        #[test]
+        fn fail_on_timeout() {
+            let error = run_command(&shell("sleep 5"), Duration::from_millis(100), 1024)
+                .expect_err("Command times out");
+
+            assert_eq!(error.to_string(), "sh did not exit within 100ms");
+        }
+
+        #[test]
+        fn fail_on_output_left_open() {
+            let error = run_command(
+                &shell("sleep 5 & echo temperature=21.5"),
+                Duration::from_millis(500),
+                1024,
+            )
+            .expect_err("Output is not closed");
+
+            assert_eq!(
+                error.to_string(),
+                "Output of sh was not closed within 500ms: timed out waiting on channel"
+            );
+        }
+
+        #[test]
+        fn fail_on_exit_status() {
+            let error = run_command(&shell("exit 3"), Duration::from_secs(5), 1024)
+                .expect_err("Command fails");
+
+            assert_eq!(error.to_string(), "sh failed with exit status: 3");
+        }
+
+        #[test]
+        fn fail_on_large_output() {
+            let error = run_command(&shell("echo temperature=21.5"), Duration::from_secs(5), 8)
+                .expect_err("Output is too large");
+
+            assert_eq!(
+                format!("{error:#}"),
+                "Could not read the output of sh: Output exceeds 8 bytes"
+            );
+        }
+    }
+
+    mod for_coordinates {
+        use crate::providers::command::Command;
+        use crate::providers::units::Coordinates;
+        use crate::providers::{WeatherProvider, WeatherRequest};
+        use moka::sync::Cache;
+        use pretty_assertions::assert_eq;
+        use reqwest::blocking::Client;
+        use std::fs;
+
+        fn request(name: &str) -> WeatherRequest<Coordinates> {
+            WeatherRequest {
+                name: name.into(),
+                query: Coordinates {
+                    latitude: 52.5_f64.into(),
+                    longitude: 13.4_f64.into(),
+                },
+            }
+        }
+
+        #[test]
+        fn cache_file_contents() {
+            let directory = tempfile::tempdir().expect("Temporary directory can be created");
+            let file = directory.path().join("weathermen.env");
+            fs::write(&file, "temperature=21.5\n").expect("Writing works");
+            let provider: Command = toml::from_str(&format!(
+                "location = \"Living room\"\nfile = {:?}",
+                file.display().to_string()
+            ))
+            .expect("Valid configuration");
+
+            let first = provider
+                .for_coordinates(&Client::new(), &Cache::new(1), &request("Living room"))
+                .expect("Weather is read");
+            fs::write(&file, "temperature=22.5\n").expect("Writing works");
+            let second = provider
+                .for_coordinates(&Client::new(), &Cache::new(1), &request("Living room"))
+                .expect("Weather is read");
+
+            assert_eq!(first.source, "local.command");
+            assert_eq!(
+                f64::from(second.temperature).to_string(),
+                "21.5",
+                "Output is cached for the refresh interval"
+            );
+        }
+
+        #[test]
+        fn serve_configured_location() {
+            let provider: Command =
+                toml::from_str("location = \"Living room\"\ncommand = [\"read-bme280\"]")
+                    .expect("Valid configuration");
+
+            assert!(provider.serves("Living room"), "Location is served");
+            assert!(!provider.serves("Garden"), "Location is not served");
+        }
+
+        #[test]
+        fn fail_for_other_location() {
+            let provider: Command =
+                toml::from_str("location = \"Living room\"\ncommand = [\"read-bme280\"]")
+                    .expect("Valid configuration");
+
+            let error = provider
+                .for_coordinates(&Client::new(), &Cache::new(1), &request("Garden"))
+                .expect_err("Location is not served");
+
+            assert_eq!(
+                error.to_string(),
+                "Command provider only serves Living room, not Garden"
+            );
+        }
+    }
+}
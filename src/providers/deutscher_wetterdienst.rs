@@ -1,31 +1,104 @@
-use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
-use crate::providers::units::{Celsius, Coordinate, Coordinates, Ratio};
+use crate::providers::http_request::{
+    decode_latin1_body, request_cached, Configuration, HttpCacheRequest,
+};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::station_lookup::{find_closest_weather_station, Station};
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, MetersPerSecond, Millimeters, Ratio,
+};
 use crate::providers::{
     calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
 };
 use anyhow::{anyhow, Context};
 use chrono::Utc;
-use const_format::concatcp;
 use csv::Trim;
-use geo::{Closest, ClosestPoint, MultiPoint, Point};
-use log::{debug, trace};
+use log::{debug, trace, warn};
 use reqwest::blocking::Client;
 use reqwest::{Method, Url};
-use serde::{Deserialize, Serialize};
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt::Debug;
 use std::io::{Cursor, Read};
 use std::time::Duration;
 use zip::ZipArchive;
 
 const SOURCE_URI: &str = "de.dwd";
-const BASE_URL: &str = "https://opendata.dwd.de/climate_environment/CDC/observations_germany/climate/10_minutes/air_temperature/now";
-const STATION_LIST_URL: &str = concatcp!(BASE_URL, "/zehn_now_tu_Beschreibung_Stationen.txt");
+const BASE_URL: &str =
+    "https://opendata.dwd.de/climate_environment/CDC/observations_germany/climate/10_minutes";
+const MISSING_VALUE: &str = "-999";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DeutscherWetterdienst {
+    #[serde(default = "default_products")]
+    products: Vec<Product>,
     #[serde(flatten)]
     cache: Configuration,
 }
 
+fn default_products() -> Vec<Product> {
+    vec![Product::AirTemperature]
+}
+
+/// The 10-minute products share the same directory layout and archive format, but each is measured
+/// by a different set of stations
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Product {
+    AirTemperature,
+    Wind,
+    Precipitation,
+    Solar,
+    ExtremeWind,
+}
+
+impl Product {
+    const fn directory(self) -> &'static str {
+        match self {
+            Self::AirTemperature => "air_temperature",
+            Self::Wind => "wind",
+            Self::Precipitation => "precipitation",
+            Self::Solar => "solar",
+            Self::ExtremeWind => "extreme_wind",
+        }
+    }
+
+    const fn station_list_abbreviation(self) -> &'static str {
+        match self {
+            Self::AirTemperature => "tu",
+            Self::Wind => "ff",
+            Self::Precipitation => "rr",
+            Self::Solar => "sd",
+            Self::ExtremeWind => "fx",
+        }
+    }
+
+    const fn archive_name(self) -> &'static str {
+        match self {
+            Self::AirTemperature => "TU",
+            Self::Wind => "wind",
+            Self::Precipitation => "nieder",
+            Self::Solar => "SOLAR",
+            Self::ExtremeWind => "extrema_wind",
+        }
+    }
+
+    fn station_list_url(self) -> String {
+        format!(
+            "{BASE_URL}/{}/now/zehn_now_{}_Beschreibung_Stationen.txt",
+            self.directory(),
+            self.station_list_abbreviation()
+        )
+    }
+
+    fn measurement_url(self, station_id: &str) -> String {
+        format!(
+            "{BASE_URL}/{}/now/10minutenwerte_{}_{station_id}_now.zip",
+            self.directory(),
+            self.archive_name()
+        )
+    }
+}
+
 #[derive(Deserialize, Debug, PartialEq, Clone)]
 struct WeatherStation {
     #[serde(rename = "Stations_id")]
@@ -38,6 +111,15 @@ struct WeatherStation {
     longitude: Coordinate,
 }
 
+impl Station for WeatherStation {
+    fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude.clone(),
+            longitude: self.longitude.clone(),
+        }
+    }
+}
+
 fn weather_station_format_to_csv(data: &str, delimiter: char) -> String {
     data.split(['\n', '\r'])
         .enumerate()
@@ -95,37 +177,6 @@ fn parse_weather_station_list_csv(data: &str) -> anyhow::Result<Vec<WeatherStati
         .context("Failed to parse weather station list CSV file")
 }
 
-fn find_closest_weather_station<'stations>(
-    coords: &Coordinates,
-    weather_stations: &'stations [WeatherStation],
-) -> anyhow::Result<&'stations WeatherStation> {
-    let point: Point<f64> = Point::new(
-        coords.longitude.clone().into(),
-        coords.latitude.clone().into(),
-    );
-    let points = MultiPoint::new(
-        weather_stations
-            .iter()
-            .map(|s| Point::new(s.longitude.clone().into(), s.latitude.clone().into()))
-            .collect(),
-    );
-
-    match points.closest_point(&point) {
-        Closest::SinglePoint(closest_point) | Closest::Intersection(closest_point) => {
-            let matching_station = weather_stations
-                .iter()
-                .find(|station| {
-                    station.longitude == closest_point.x().into()
-                        && station.latitude == closest_point.y().into()
-                })
-                .ok_or_else(|| anyhow!("Could not find matching station"))?;
-
-            Ok(matching_station)
-        }
-        Closest::Indeterminate => Err(anyhow!("Could not find closest point")),
-    }
-}
-
 fn is_measurement_file(file_name: &str) -> bool {
     let file_path = std::path::Path::new(file_name);
 
@@ -181,6 +232,44 @@ struct Measurement {
     _dew_point_temperature_200_centimeters: Celsius,
 }
 
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct WindMeasurement {
+    #[serde(rename = "FF_10", deserialize_with = "deserialize_reported")]
+    wind_speed: Option<f64>,
+    #[serde(rename = "DD_10", deserialize_with = "deserialize_reported")]
+    wind_direction: Option<f64>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct PrecipitationMeasurement {
+    #[serde(rename = "RWS_10", deserialize_with = "deserialize_reported")]
+    precipitation: Option<f64>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct SolarMeasurement {
+    #[serde(rename = "SD_10", deserialize_with = "deserialize_reported")]
+    sunshine_hours: Option<f64>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct ExtremeWindMeasurement {
+    #[serde(rename = "FX_10", deserialize_with = "deserialize_reported")]
+    wind_gust: Option<f64>,
+}
+
+/// Values that are not reported by a station are given as `-999`
+fn deserialize_reported<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+
+    match value.trim() {
+        MISSING_VALUE => Ok(None),
+        v => v.parse().map(Some).map_err(D::Error::custom),
+    }
+}
+
 mod minute_precision_date_format {
     use chrono::{DateTime, NaiveDateTime, Utc};
     use serde::de::Error;
@@ -199,27 +288,24 @@ mod minute_precision_date_format {
     }
 }
 
-fn parse_measurement_data_csv(data: &String) -> anyhow::Result<Vec<Measurement>> {
+fn parse_measurement_data_csv<T: DeserializeOwned>(data: &str) -> anyhow::Result<Vec<T>> {
     let reader = csv::ReaderBuilder::new()
         .delimiter(b';')
         .double_quote(false)
         .trim(Trim::All)
         .from_reader(data.as_bytes());
 
-    Ok(reader
-        .into_deserialize::<Measurement>()
-        .collect::<Result<_, _>>()?)
+    Ok(reader.into_deserialize::<T>().collect::<Result<_, _>>()?)
 }
 
 fn reqwest_cached_measurement_csv(
     cache: &HttpRequestCache,
     client: &Client,
-    station_id: &String,
+    product: Product,
+    station_id: &str,
 ) -> anyhow::Result<String> {
     let method = Method::GET;
-    let url = Url::parse(&format!(
-        "{BASE_URL}/10minutenwerte_TU_{station_id}_now.zip"
-    ))?;
+    let url = Url::parse(&product.measurement_url(station_id))?;
 
     request_cached(&HttpCacheRequest::new(
         SOURCE_URI,
@@ -231,6 +317,138 @@ fn reqwest_cached_measurement_csv(
     ))
 }
 
+fn latest_measurement<T: DeserializeOwned + Debug>(
+    client: &Client,
+    cache: &HttpRequestCache,
+    product: Product,
+    coordinates: &Coordinates,
+) -> anyhow::Result<(WeatherStation, T)> {
+    let stations = request_cached(&HttpCacheRequest::new(
+        SOURCE_URI,
+        client,
+        cache,
+        &Method::GET,
+        &Url::parse(&product.station_list_url())?,
+        |body| parse_weather_station_list_csv(&decode_latin1_body(body)),
+    ))?;
+
+    let closest_station = find_closest_weather_station(coordinates, &stations)?;
+    trace!("Found closest weather station for {product:?} {closest_station:?}");
+
+    let measurement_csv =
+        reqwest_cached_measurement_csv(cache, client, product, &closest_station.station_id)?;
+    let latest = parse_measurement_data_csv::<T>(&measurement_csv)?
+        .pop()
+        .ok_or_else(|| anyhow!("Empty measurement list"))?;
+    debug!("Using latest {product:?} measurement: {latest:?}");
+
+    Ok((closest_station.clone(), latest))
+}
+
+/// Products are reported by the closest station measuring them, which is not necessarily the
+/// station measuring air temperature. Every station gets its own series, so its city, distance and
+/// coordinates describe where the values were measured
+fn station_series<'series>(
+    series: &'series mut Vec<(String, Weather)>,
+    station: &WeatherStation,
+    request: &WeatherRequest<Coordinates>,
+) -> &'series mut Weather {
+    if !series.iter().any(|(id, _)| *id == station.station_id) {
+        let coordinates = station.coordinates();
+        let distance = calculate_distance(&request.query, &coordinates);
+        series.push((
+            station.station_id.clone(),
+            Weather {
+                source: SOURCE_URI.into(),
+                location: request.name.clone(),
+                city: Some(station.name.clone()),
+                coordinates,
+                distance: Some(distance),
+                without_temperature: true,
+                ..Weather::default()
+            },
+        ));
+    }
+
+    series
+        .iter_mut()
+        .find_map(|(id, weather)| (*id == station.station_id).then_some(weather))
+        .expect("Series of the station was just added")
+}
+
+fn add_product(
+    series: &mut Vec<(String, Weather)>,
+    client: &Client,
+    cache: &HttpRequestCache,
+    product: Product,
+    request: &WeatherRequest<Coordinates>,
+) -> anyhow::Result<WeatherStation> {
+    let station = match product {
+        Product::AirTemperature => {
+            return Err(anyhow!("Air temperature is not an additional product"))
+        }
+        Product::Wind => {
+            let (station, m) =
+                latest_measurement::<WindMeasurement>(client, cache, product, &request.query)?;
+            let weather = station_series(series, &station, request);
+            weather.wind_speed = m.wind_speed.map(MetersPerSecond::from);
+            weather.wind_direction = m.wind_direction.map(Degrees::from);
+            station
+        }
+        Product::Precipitation => {
+            let (station, m) = latest_measurement::<PrecipitationMeasurement>(
+                client,
+                cache,
+                product,
+                &request.query,
+            )?;
+            station_series(series, &station, request).precipitation =
+                m.precipitation.map(Millimeters::from);
+            station
+        }
+        Product::Solar => {
+            let (station, m) =
+                latest_measurement::<SolarMeasurement>(client, cache, product, &request.query)?;
+            station_series(series, &station, request).sunshine_duration = m
+                .sunshine_hours
+                .and_then(|hours| Duration::try_from_secs_f64(hours * 3600.0).ok());
+            station
+        }
+        Product::ExtremeWind => {
+            let (station, m) = latest_measurement::<ExtremeWindMeasurement>(
+                client,
+                cache,
+                product,
+                &request.query,
+            )?;
+            station_series(series, &station, request).wind_gust =
+                m.wind_gust.map(MetersPerSecond::from);
+            station
+        }
+    };
+
+    Ok(station)
+}
+
+impl DeutscherWetterdienst {
+    /// Configured products without duplicates, air temperature is always fetched first as it
+    /// provides the mandatory temperature
+    fn products(&self) -> Vec<Product> {
+        let mut products = vec![Product::AirTemperature];
+        for &product in &self.products {
+            if !products.contains(&product) {
+                products.push(product);
+            }
+        }
+
+        products
+    }
+}
+
+impl RegisteredProvider for DeutscherWetterdienst {
+    const KEY: &'static str = "deutscher_wetterdienst";
+}
+
 impl WeatherProvider for DeutscherWetterdienst {
     fn id(&self) -> &str {
         SOURCE_URI
@@ -242,63 +460,58 @@ impl WeatherProvider for DeutscherWetterdienst {
         cache: &HttpRequestCache,
         request: &WeatherRequest<Coordinates>,
     ) -> anyhow::Result<Weather> {
-        let stations = request_cached(&HttpCacheRequest::new(
-            SOURCE_URI,
+        let (closest_station, latest_measurement) = latest_measurement::<Measurement>(
             client,
             cache,
-            &Method::GET,
-            &Url::parse(STATION_LIST_URL)?,
-            |body| {
-                let str: String = body
-                    .iter()
-                    .filter_map(|&c| char::from_u32(c.into()))
-                    .collect();
-
-                parse_weather_station_list_csv(&str)
-            },
-        ))?;
-
-        let closest_station = find_closest_weather_station(&request.query, &stations)?;
-        trace!("Found closest weather station {:?}", closest_station);
-        let measurement_csv =
-            reqwest_cached_measurement_csv(cache, client, &closest_station.station_id)?;
-        let measurements = parse_measurement_data_csv(&measurement_csv)?;
-
-        match &*measurements {
-            [.., latest_measurement] => {
-                debug!(
-                    "Using latest measurement from {}: {:?}",
-                    latest_measurement.time,
-                    latest_measurement.clone()
-                );
-
-                let coordinates = Coordinates {
-                    latitude: closest_station.latitude.clone(),
-                    longitude: closest_station.longitude.clone(),
-                };
-
-                let distance = calculate_distance(&request.query, &coordinates);
-
-                Ok(Weather {
-                    source: SOURCE_URI.into(),
-                    location: request.name.clone(),
-                    city: Some(closest_station.name.clone()),
-                    coordinates,
-                    distance: Some(distance),
-                    temperature: latest_measurement.temperature_200_centimers,
-                    relative_humidity: Some(latest_measurement.relative_humidity_200_centimeters),
-                })
+            Product::AirTemperature,
+            &request.query,
+        )?;
+
+        let coordinates = closest_station.coordinates();
+        let distance = calculate_distance(&request.query, &coordinates);
+
+        let weather = Weather {
+            source: SOURCE_URI.into(),
+            location: request.name.clone(),
+            city: Some(closest_station.name),
+            coordinates,
+            distance: Some(distance),
+            temperature: latest_measurement.temperature_200_centimers,
+            relative_humidity: Some(latest_measurement.relative_humidity_200_centimeters),
+            ..Weather::default()
+        };
+        let mut series = vec![(closest_station.station_id, weather)];
+
+        // Only air temperature is mandatory, every other product is reported if available
+        for product in self.products().into_iter().skip(1) {
+            match add_product(&mut series, client, cache, product, request) {
+                Ok(station) => trace!("Using {product:?} measured at {station:?}"),
+                Err(e) => warn!("Could not fetch product {product:?}: {e:?}"),
             }
-            [] => Err(anyhow!("Empty measurement list")),
         }
+
+        // Series of the stations measuring other products are exported alongside the series of the
+        // air temperature station
+        let mut stations = series
+            .into_iter()
+            .map(|(_, station_weather)| station_weather);
+        let air_temperature = stations
+            .next()
+            .ok_or_else(|| anyhow!("No air temperature station"))?;
+
+        Ok(Weather {
+            modules: stations.collect(),
+            ..air_temperature
+        })
     }
 
     fn refresh_interval(&self) -> Duration {
         self.cache.refresh_interval
     }
 
+    // One station list and one measurement archive per product
     fn cache_cardinality(&self) -> usize {
-        2
+        self.products().len().saturating_mul(2)
     }
 }
 
@@ -365,49 +578,10 @@ broken\n\
         }
     }
 
-    mod find_closes_weather_station {
+    mod parse_measurement_data_csv {
         use crate::providers::deutscher_wetterdienst::{
-            find_closest_weather_station, WeatherStation,
+            parse_measurement_data_csv, ExtremeWindMeasurement, Measurement, WindMeasurement,
         };
-        use crate::providers::units::Coordinates;
-        use pretty_assertions::assert_eq;
-
-        #[test]
-        fn find_closest_station_to_a_coordinate() {
-            assert_eq!(
-                find_closest_weather_station(
-                    &Coordinates {
-                        latitude: 48.11591_f64.into(),
-                        longitude: 11.570_906_f64.into(),
-                    },
-                    &[
-                        WeatherStation {
-                            station_id: "03379".into(),
-                            name: "M\u{fc}nchen-Stadt".into(),
-                            latitude: 48.1632_f64.into(),
-                            longitude: 11.5429_f64.into(),
-                        },
-                        WeatherStation {
-                            station_id: "01262".into(),
-                            name: "M\u{fc}nchen-Flughafen".into(),
-                            latitude: 48.3477_f64.into(),
-                            longitude: 11.8134_f64.into(),
-                        },
-                    ]
-                )
-                .expect("Should find something"),
-                &WeatherStation {
-                    station_id: "03379".into(),
-                    name: "M\u{fc}nchen-Stadt".into(),
-                    latitude: 48.1632_f64.into(),
-                    longitude: 11.5429_f64.into(),
-                }
-            );
-        }
-    }
-
-    mod parse_measurement_data_csv {
-        use crate::providers::deutscher_wetterdienst::{parse_measurement_data_csv, Measurement};
         use crate::providers::units::Ratio;
         use chrono::{DateTime, Utc};
         use pretty_assertions::assert_eq;
@@ -415,10 +589,9 @@ broken\n\
         #[test]
         fn parse_example() {
             assert_eq!(
-                &*parse_measurement_data_csv(
-                    &"STATIONS_ID;MESS_DATUM;  QN;PP_10;TT_10;TM5_10;RF_10;TD_10;eor\n\
-            379;202301120000;    2;   -999;   5.1;   2.5;  82.6;   2.4;eor"
-                        .to_owned(),
+                &*parse_measurement_data_csv::<Measurement>(
+                    "STATIONS_ID;MESS_DATUM;  QN;PP_10;TT_10;TM5_10;RF_10;TD_10;eor\n\
+            379;202301120000;    2;   -999;   5.1;   2.5;  82.6;   2.4;eor",
                 )
                 .expect("Parsing works"),
                 [Measurement {
@@ -434,5 +607,173 @@ broken\n\
                 }]
             );
         }
+
+        #[test]
+        fn parse_wind_example() {
+            assert_eq!(
+                &*parse_measurement_data_csv::<WindMeasurement>(
+                    "STATIONS_ID;MESS_DATUM;  QN;FF_10;DD_10;eor\n\
+            433;202610181000;    3;   4.2;   240;eor",
+                )
+                .expect("Parsing works"),
+                [WindMeasurement {
+                    wind_speed: Some(4.2_f64),
+                    wind_direction: Some(240.0_f64),
+                }]
+            );
+        }
+
+        #[test]
+        fn parse_missing_values() {
+            assert_eq!(
+                &*parse_measurement_data_csv::<ExtremeWindMeasurement>(
+                    "STATIONS_ID;MESS_DATUM;  QN;FX_10;FNX_10;FMX_10;DX_10;eor\n\
+            433;202610181000;    3;   -999;   -999;   -999;   -999;eor",
+                )
+                .expect("Parsing works"),
+                [ExtremeWindMeasurement { wind_gust: None }]
+            );
+        }
+    }
+
+    mod product {
+        use crate::providers::deutscher_wetterdienst::Product;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn precipitation_urls() {
+            assert_eq!(
+                Product::Precipitation.station_list_url(),
+                "https://opendata.dwd.de/climate_environment/CDC/observations_germany/climate/10_minutes/precipitation/now/zehn_now_rr_Beschreibung_Stationen.txt"
+            );
+            assert_eq!(
+                Product::Precipitation.measurement_url("00433"),
+                "https://opendata.dwd.de/climate_environment/CDC/observations_germany/climate/10_minutes/precipitation/now/10minutenwerte_nieder_00433_now.zip"
+            );
+        }
+    }
+
+    mod products {
+        use crate::providers::deutscher_wetterdienst::{DeutscherWetterdienst, Product};
+        use crate::providers::WeatherProvider;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn deduplicate_products() {
+            let provider: DeutscherWetterdienst =
+                toml::from_str(r#"products = ["wind", "precipitation", "wind"]"#)
+                    .expect("Valid configuration");
+
+            assert_eq!(
+                provider.products(),
+                vec![
+                    Product::AirTemperature,
+                    Product::Wind,
+                    Product::Precipitation
+                ]
+            );
+            assert_eq!(provider.cache_cardinality(), 6);
+        }
+
+        #[test]
+        fn air_temperature_only_once() {
+            let provider: DeutscherWetterdienst =
+                toml::from_str(r#"products = ["air_temperature"]"#).expect("Valid configuration");
+
+            assert_eq!(provider.products(), vec![Product::AirTemperature]);
+            assert_eq!(provider.cache_cardinality(), 2);
+        }
+    }
+
+    mod station_series {
+        use crate::providers::deutscher_wetterdienst::{station_series, WeatherStation};
+        use crate::providers::units::Coordinates;
+        use crate::providers::{Weather, WeatherRequest};
+        use pretty_assertions::assert_eq;
+
+        fn station(station_id: &str, name: &str, latitude: f64) -> WeatherStation {
+            WeatherStation {
+                station_id: station_id.into(),
+                name: name.into(),
+                latitude: latitude.into(),
+                longitude: 13.0_f64.into(),
+            }
+        }
+
+        #[test]
+        fn series_per_station() {
+            let request = WeatherRequest {
+                name: "Berlin".into(),
+                query: Coordinates {
+                    latitude: 52.5_f64.into(),
+                    longitude: 13.0_f64.into(),
+                },
+            };
+            let mut series = vec![(
+                "00433".into(),
+                Weather {
+                    city: Some("Berlin-Tempelhof".into()),
+                    ..Weather::default()
+                },
+            )];
+
+            station_series(
+                &mut series,
+                &station("00433", "Berlin-Tempelhof", 52.5),
+                &request,
+            )
+            .wind_gust = Some(7.5_f64.into());
+            station_series(
+                &mut series,
+                &station("00427", "Berlin-Brandenburg", 52.4),
+                &request,
+            )
+            .wind_speed = Some(3.5_f64.into());
+            station_series(
+                &mut series,
+                &station("00427", "Berlin-Brandenburg", 52.4),
+                &request,
+            )
+            .precipitation = Some(0.2_f64.into());
+
+            let summary: Vec<_> = series
+                .iter()
+                .map(|(id, weather)| {
+                    (
+                        id.as_str(),
+                        weather.city.as_deref(),
+                        weather.distance.is_some(),
+                        weather.without_temperature,
+                        weather.wind_gust.is_some(),
+                        weather.wind_speed.is_some(),
+                        weather.precipitation.is_some(),
+                    )
+                })
+                .collect();
+
+            assert_eq!(
+                summary,
+                vec![
+                    (
+                        "00433",
+                        Some("Berlin-Tempelhof"),
+                        false,
+                        false,
+                        true,
+                        false,
+                        false
+                    ),
+                    (
+                        "00427",
+                        Some("Berlin-Brandenburg"),
+                        true,
+                        true,
+                        false,
+                        true,
+                        true
+                    ),
+                ]
+            );
+        }
     }
 }
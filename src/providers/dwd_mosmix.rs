@@ -0,0 +1,496 @@
+use crate::providers::http_request::{decode_latin1_body, request_cached, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::station_lookup::{find_closest_weather_station, Station};
+use crate::providers::units::{
+    Coordinate, Coordinates, Degrees, Kelvin, MetersPerSecond, Millimeters, Pascals, Ratio,
+    ToCelsius, ToHectopascals,
+};
+use crate::providers::{
+    calculate_distance, Forecast, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use log::{debug, trace};
+use quick_xml::de;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use zip::ZipArchive;
+
+const SOURCE_URI: &str = "de.dwd.mosmix";
+const STATION_LIST_URL: &str = "https://www.dwd.de/DE/leistungen/met_verfahren_mosmix/mosmix_stationskatalog.cfg?view=nasPublication";
+const BASE_URL: &str =
+    "https://opendata.dwd.de/weather/local_forecasts/mos/MOSMIX_L/single_stations";
+
+/// Only `MOSMIX_L` is published per station, `MOSMIX_S` is only available as a file for all stations
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DwdMosmix {
+    #[serde(default = "default_forecast_hours")]
+    forecast_hours: u32,
+    #[serde(default = "default_refresh_interval")]
+    #[serde(with = "humantime_serde")]
+    refresh_interval: Duration,
+}
+
+const fn default_forecast_hours() -> u32 {
+    24
+}
+
+// MOSMIX_L is issued four times a day
+const fn default_refresh_interval() -> Duration {
+    Duration::from_secs(60 * 60)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct WeatherStation {
+    station_id: String,
+    name: String,
+    latitude: Coordinate,
+    longitude: Coordinate,
+}
+
+impl Station for WeatherStation {
+    fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude.clone(),
+            longitude: self.longitude.clone(),
+        }
+    }
+}
+
+/// The station catalog gives coordinates as degrees and minutes, e.g. `48.10` is 48°10'
+fn parse_degrees_minutes(value: &str) -> anyhow::Result<Coordinate> {
+    let (degrees, minutes) = value.split_once('.').unwrap_or((value, "0"));
+    let sign = if degrees.starts_with('-') {
+        -1.0_f64
+    } else {
+        1.0_f64
+    };
+
+    Ok((sign * (degrees.parse::<f64>()?.abs() + minutes.parse::<f64>()? / 60.0)).into())
+}
+
+fn parse_station_catalog(data: &str) -> anyhow::Result<Vec<WeatherStation>> {
+    data.lines()
+        .skip_while(|line| !line.starts_with("-----"))
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+
+            match columns.as_slice() {
+                [station_id, _icao, name @ .., latitude, longitude, _elevation] => {
+                    Ok(WeatherStation {
+                        station_id: (*station_id).to_owned(),
+                        name: name.join(" "),
+                        latitude: parse_degrees_minutes(latitude)?,
+                        longitude: parse_degrees_minutes(longitude)?,
+                    })
+                }
+                _ => Err(anyhow!("Invalid station catalog line {line}")),
+            }
+        })
+        .collect::<anyhow::Result<_>>()
+        .context("Failed to parse MOSMIX station catalog")
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct Kml {
+    document: KmlDocument,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct KmlDocument {
+    extended_data: KmlProductData,
+    placemark: KmlPlacemark,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct KmlProductData {
+    product_definition: KmlProductDefinition,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct KmlProductDefinition {
+    forecast_time_steps: KmlTimeSteps,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct KmlTimeSteps {
+    time_step: Vec<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct KmlPlacemark {
+    #[serde(rename = "description")]
+    name: String,
+    extended_data: KmlForecastData,
+    point: KmlPoint,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct KmlForecastData {
+    forecast: Vec<KmlForecast>,
+}
+
+#[derive(Deserialize, Debug)]
+struct KmlForecast {
+    #[serde(rename = "@elementName")]
+    element_name: String,
+    value: String,
+}
+
+// Longitude, latitude and elevation separated by commas
+#[derive(Deserialize, Debug)]
+struct KmlPoint {
+    coordinates: String,
+}
+
+#[derive(Debug)]
+struct StationForecast {
+    name: String,
+    coordinates: Coordinates,
+    time_steps: Vec<DateTime<Utc>>,
+    elements: HashMap<String, Vec<Option<f64>>>,
+}
+
+fn is_kml_file(file_name: &str) -> bool {
+    Path::new(file_name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("kml"))
+}
+
+fn read_kml_from_kmz(buf: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut zip = ZipArchive::new(Cursor::new(buf))?;
+
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+
+        if !is_kml_file(file.name()) {
+            trace!("Skipping file in KMZ: {}", file.name());
+            continue;
+        }
+
+        debug!("Found KML file in KMZ: {}", file.name());
+
+        // The KML declares its own encoding, so leave decoding to the XML parser
+        let mut kml = vec![];
+        file.read_to_end(&mut kml)?;
+
+        return Ok(kml);
+    }
+
+    Err(anyhow!("Could not find KML file in KMZ archive"))
+}
+
+fn parse_kml(data: &[u8]) -> anyhow::Result<StationForecast> {
+    let kml: Kml = de::from_reader(data).context("Failed to parse MOSMIX KML")?;
+    let placemark = kml.document.placemark;
+
+    let coordinates = match placemark
+        .point
+        .coordinates
+        .split(',')
+        .map(|v| v.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()?
+        .as_slice()
+    {
+        [longitude, latitude, ..] => Coordinates {
+            latitude: (*latitude).into(),
+            longitude: (*longitude).into(),
+        },
+        _ => return Err(anyhow!("Invalid placemark coordinates")),
+    };
+
+    // Missing values are reported as "-"
+    let elements = placemark
+        .extended_data
+        .forecast
+        .into_iter()
+        .map(|forecast| {
+            let values = forecast
+                .value
+                .split_whitespace()
+                .map(|v| v.parse::<f64>().ok())
+                .collect();
+
+            (forecast.element_name, values)
+        })
+        .collect();
+
+    Ok(StationForecast {
+        name: placemark.name,
+        coordinates,
+        time_steps: kml
+            .document
+            .extended_data
+            .product_definition
+            .forecast_time_steps
+            .time_step,
+        elements,
+    })
+}
+
+fn load_station_forecast(body: &[u8]) -> anyhow::Result<StationForecast> {
+    parse_kml(&read_kml_from_kmz(body)?)
+}
+
+impl StationForecast {
+    fn value(&self, element: &str, index: usize) -> Option<f64> {
+        self.elements
+            .get(element)
+            .and_then(|values| values.get(index).copied().flatten())
+    }
+
+    fn forecast(&self, index: usize, hours_ahead: u32) -> Forecast {
+        let value = |element| self.value(element, index);
+
+        Forecast {
+            hours_ahead,
+            temperature: value("TTT").map(|v| Kelvin::from_f64(v).to_celsius()),
+            dew_point: value("Td").map(|v| Kelvin::from_f64(v).to_celsius()),
+            pressure: value("PPPP").map(|v| Pascals::from(v).to_hectopascals()),
+            wind_speed: value("FF").map(MetersPerSecond::from),
+            wind_gust: value("FX1").map(MetersPerSecond::from),
+            wind_direction: value("DD").map(Degrees::from),
+            cloud_cover: value("N").map(Ratio::Percentage),
+            precipitation: value("RR1c").map(Millimeters::from),
+        }
+    }
+
+    /// The latest time step that is not in the future as zero hours ahead and up to `hours`
+    /// following steps
+    fn forecast_steps(&self, now: DateTime<Utc>, hours: u32) -> Option<Vec<Forecast>> {
+        let current_index = self
+            .time_steps
+            .iter()
+            .rposition(|time| *time <= now)
+            .unwrap_or(0);
+        let current_time = self.time_steps.get(current_index)?;
+
+        let steps = self
+            .time_steps
+            .iter()
+            .enumerate()
+            .skip(current_index)
+            .filter_map(|(index, time)| {
+                let hours_ahead =
+                    u32::try_from(time.signed_duration_since(current_time).num_hours()).ok()?;
+                (0..=hours)
+                    .contains(&hours_ahead)
+                    .then(|| self.forecast(index, hours_ahead))
+            })
+            .collect();
+
+        Some(steps)
+    }
+}
+
+impl RegisteredProvider for DwdMosmix {
+    const KEY: &'static str = "dwd_mosmix";
+}
+
+impl WeatherProvider for DwdMosmix {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let stations = request_cached(&HttpCacheRequest::new(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &Url::parse(STATION_LIST_URL)?,
+            |body| parse_station_catalog(&decode_latin1_body(body)),
+        ))?;
+
+        let closest_station = find_closest_weather_station(&request.query, &stations)?;
+        trace!("Found closest MOSMIX station {:?}", closest_station);
+
+        let station_id = &closest_station.station_id;
+        let station_forecast = request_cached(&HttpCacheRequest::new(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &Url::parse(&format!(
+                "{BASE_URL}/{station_id}/kml/MOSMIX_L_LATEST_{station_id}.kmz"
+            ))?,
+            |body| load_station_forecast(body),
+        ))?;
+
+        let forecast = station_forecast
+            .forecast_steps(SystemTime::now().into(), self.forecast_hours)
+            .ok_or_else(|| anyhow!("Empty forecast for station {}", closest_station.name))?;
+
+        let distance = calculate_distance(&request.query, &station_forecast.coordinates);
+
+        Ok(Weather {
+            source: SOURCE_URI.into(),
+            location: request.name.clone(),
+            city: Some(station_forecast.name),
+            coordinates: station_forecast.coordinates,
+            distance: Some(distance),
+            // MOSMIX has no observations, so even the current hour is only exported as forecast
+            forecast,
+            without_temperature: true,
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.refresh_interval
+    }
+
+    fn cache_cardinality(&self) -> usize {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse_station_catalog {
+        use crate::providers::dwd_mosmix::{parse_station_catalog, WeatherStation};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_short_catalog() {
+            assert_eq!(
+                parse_station_catalog(
+                    "ID    ICAO NAME                 LAT    LON     ELEV\n\
+                    ----- ---- -------------------- -----  ------- -----\n\
+                    10865 ---- MUENCHEN-STADT        48.10   11.33   515\n\
+                    01001 ENJA JAN MAYEN             70.56   -8.40    10\n"
+                )
+                .expect("Parsing works"),
+                vec![
+                    WeatherStation {
+                        station_id: "10865".into(),
+                        name: "MUENCHEN-STADT".into(),
+                        latitude: (48.0_f64 + 10.0_f64 / 60.0_f64).into(),
+                        longitude: (11.0_f64 + 33.0_f64 / 60.0_f64).into(),
+                    },
+                    WeatherStation {
+                        station_id: "01001".into(),
+                        name: "JAN MAYEN".into(),
+                        latitude: (70.0_f64 + 56.0_f64 / 60.0_f64).into(),
+                        longitude: (-8.0_f64 - 40.0_f64 / 60.0_f64).into(),
+                    },
+                ]
+            );
+        }
+    }
+
+    mod parse_kml {
+        use crate::providers::dwd_mosmix::parse_kml;
+        use crate::providers::units::{Celsius, Hectopascals, Millimeters, Ratio};
+        use chrono::DateTime;
+        use pretty_assertions::assert_eq;
+
+        const KML: &str = r#"<?xml version="1.0" encoding="ISO-8859-1" standalone="yes"?>
+<kml:kml xmlns:dwd="https://opendata.dwd.de/weather/lib/pointforecast_dwd_extension_V1_0.xsd" xmlns:gx="http://www.google.com/kml/ext/2.2" xmlns:xal="urn:oasis:names:tc:ciq:xsdschema:xAL:2.0" xmlns:kml="http://www.opengis.net/kml/2.2" xmlns:atom="http://www.w3.org/2005/Atom">
+    <kml:Document>
+        <kml:ExtendedData>
+            <dwd:ProductDefinition>
+                <dwd:Issuer>Deutscher Wetterdienst</dwd:Issuer>
+                <dwd:ProductID>MOSMIX</dwd:ProductID>
+                <dwd:GeneratingProcess>DWD MOSMIX hourly, Version 1.0</dwd:GeneratingProcess>
+                <dwd:IssueTime>2026-10-18T09:00:00.000Z</dwd:IssueTime>
+                <dwd:ReferencedModel>
+                    <dwd:Model dwd:name="ICON" dwd:referenceTime="2026-10-18T03:00:00Z"/>
+                </dwd:ReferencedModel>
+                <dwd:ForecastTimeSteps>
+                    <dwd:TimeStep>2026-10-18T10:00:00.000Z</dwd:TimeStep>
+                    <dwd:TimeStep>2026-10-18T11:00:00.000Z</dwd:TimeStep>
+                    <dwd:TimeStep>2026-10-18T12:00:00.000Z</dwd:TimeStep>
+                    <dwd:TimeStep>2026-10-18T13:00:00.000Z</dwd:TimeStep>
+                </dwd:ForecastTimeSteps>
+                <dwd:FormatCfg>
+                    <dwd:DefaultUndefSign>-</dwd:DefaultUndefSign>
+                </dwd:FormatCfg>
+            </dwd:ProductDefinition>
+        </kml:ExtendedData>
+        <kml:Placemark>
+            <kml:name>10865</kml:name>
+            <kml:description>MUENCHEN-STADT</kml:description>
+            <kml:ExtendedData>
+                <dwd:Forecast dwd:elementName="PPPP">
+                    <dwd:value>    101320.00    101290.00    101250.00    101210.00</dwd:value>
+                </dwd:Forecast>
+                <dwd:Forecast dwd:elementName="TTT">
+                    <dwd:value>       284.45       285.65       286.75       287.35</dwd:value>
+                </dwd:Forecast>
+                <dwd:Forecast dwd:elementName="N">
+                    <dwd:value>        88.00        75.00        63.00        50.00</dwd:value>
+                </dwd:Forecast>
+                <dwd:Forecast dwd:elementName="RR1c">
+                    <dwd:value>            -         0.10         0.00         0.00</dwd:value>
+                </dwd:Forecast>
+            </kml:ExtendedData>
+            <kml:Point>
+                <kml:coordinates>11.55,48.17,515.0</kml:coordinates>
+            </kml:Point>
+        </kml:Placemark>
+    </kml:Document>
+</kml:kml>"#;
+
+        #[test]
+        fn parse_forecast() {
+            let forecast = parse_kml(KML.as_bytes()).expect("Parsing works");
+
+            assert_eq!(forecast.name, "MUENCHEN-STADT");
+            assert_eq!(forecast.coordinates.latitude, 48.17_f64.into());
+            assert_eq!(forecast.coordinates.longitude, 11.55_f64.into());
+            assert_eq!(forecast.time_steps.len(), 4);
+            assert_eq!(forecast.value("RR1c", 0), None);
+            assert_eq!(forecast.value("RR1c", 1), Some(0.1_f64));
+            assert_eq!(forecast.value("FF", 0), None);
+        }
+
+        #[test]
+        fn select_current_and_upcoming_steps() {
+            let forecast = parse_kml(KML.as_bytes()).expect("Parsing works");
+
+            let steps = forecast
+                .forecast_steps(
+                    DateTime::parse_from_rfc3339("2026-10-18T11:20:00Z")
+                        .expect("Valid date")
+                        .into(),
+                    1,
+                )
+                .expect("Forecast available");
+            let current = steps.first().expect("Current step is present");
+
+            assert_eq!(current.hours_ahead, 0);
+            assert_eq!(current.temperature, Some(Celsius::from(12.5)));
+            assert_eq!(current.pressure, Some(Hectopascals::from(1012.9_f64)));
+            assert_eq!(current.cloud_cover, Some(Ratio::Percentage(75_f64)));
+            assert_eq!(current.precipitation, Some(Millimeters::from(0.1_f64)));
+
+            assert_eq!(
+                steps
+                    .iter()
+                    .map(|step| step.hours_ahead)
+                    .collect::<Vec<_>>(),
+                vec![0, 1]
+            );
+        }
+    }
+}
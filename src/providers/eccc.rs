@@ -0,0 +1,336 @@
+use crate::providers::http_request::{
+    decode_latin1_body, request_cached, Configuration, HttpCacheRequest,
+};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::station_lookup::{find_closest_weather_station, Station};
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, KilometersPerHour, Kilopascals, Ratio,
+    ToHectopascals, ToMetersPerSecond,
+};
+use crate::providers::{
+    calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::{anyhow, Context};
+use csv::Trim;
+use log::trace;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::str;
+use std::time::Duration;
+
+const SOURCE_URI: &str = "ca.gc.weather";
+const BASE_URL: &str = "https://dd.weather.gc.ca/citypage_weather";
+const SITE_LIST_URL: &str = "https://dd.weather.gc.ca/citypage_weather/docs/site_list_en.csv";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Eccc {
+    #[serde(flatten)]
+    cache: Configuration,
+}
+
+/// Coordinates are given with a hemisphere suffix, e.g. `43.74N` or `79.37W`
+fn parse_hemisphere_coordinate(value: &str) -> anyhow::Result<Coordinate> {
+    let (number, sign) = match value.trim() {
+        v if v.ends_with(['N', 'E']) => (v.trim_end_matches(['N', 'E']), 1.0_f64),
+        v if v.ends_with(['S', 'W']) => (v.trim_end_matches(['S', 'W']), -1.0_f64),
+        v => return Err(anyhow!("Missing hemisphere in coordinate {v}")),
+    };
+
+    Ok((number.parse::<f64>()? * sign).into())
+}
+
+fn deserialize_hemisphere_coordinate<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Coordinate, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_hemisphere_coordinate(&value).map_err(D::Error::custom)
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct Site {
+    #[serde(rename = "Codes")]
+    code: String,
+    #[serde(rename = "English Names")]
+    name: String,
+    #[serde(rename = "Province Codes")]
+    province: String,
+    #[serde(
+        rename = "Latitude",
+        deserialize_with = "deserialize_hemisphere_coordinate"
+    )]
+    latitude: Coordinate,
+    #[serde(
+        rename = "Longitude",
+        deserialize_with = "deserialize_hemisphere_coordinate"
+    )]
+    longitude: Coordinate,
+}
+
+impl Station for Site {
+    fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude.clone(),
+            longitude: self.longitude.clone(),
+        }
+    }
+}
+
+fn decode_body(body: &[u8]) -> String {
+    str::from_utf8(body).map_or_else(|_| decode_latin1_body(body), ToOwned::to_owned)
+}
+
+// The site list is preceded by a title line before the actual CSV header
+fn parse_site_list_csv(data: &str) -> anyhow::Result<Vec<Site>> {
+    let header_start = data
+        .find("Codes,")
+        .ok_or_else(|| anyhow!("Could not find site list header"))?;
+
+    let reader = csv::ReaderBuilder::new()
+        .trim(Trim::All)
+        .from_reader(data.get(header_start..).unwrap_or_default().as_bytes());
+
+    reader
+        .into_deserialize::<Site>()
+        .collect::<Result<_, _>>()
+        .context("Failed to parse site list CSV file")
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SiteData {
+    current_conditions: Option<CurrentConditions>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CurrentConditions {
+    station: Option<ObservationStation>,
+    temperature: Option<Measurement>,
+    dewpoint: Option<Measurement>,
+    pressure: Option<Measurement>,
+    relative_humidity: Option<Measurement>,
+    wind: Option<Wind>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ObservationStation {
+    #[serde(
+        rename = "@lat",
+        deserialize_with = "deserialize_hemisphere_coordinate"
+    )]
+    latitude: Coordinate,
+    #[serde(
+        rename = "@lon",
+        deserialize_with = "deserialize_hemisphere_coordinate"
+    )]
+    longitude: Coordinate,
+    #[serde(rename = "$text")]
+    name: String,
+}
+
+// Elements are present but empty if a value is currently not reported
+#[derive(Deserialize, Debug)]
+struct Measurement {
+    #[serde(rename = "$text", default)]
+    value: Option<String>,
+}
+
+impl Measurement {
+    fn number(&self) -> Option<f64> {
+        self.value.as_ref().and_then(|v| v.trim().parse().ok())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Wind {
+    speed: Option<Measurement>,
+    gust: Option<Measurement>,
+    bearing: Option<Measurement>,
+}
+
+fn number(measurement: Option<&Measurement>) -> Option<f64> {
+    measurement.and_then(Measurement::number)
+}
+
+impl RegisteredProvider for Eccc {
+    const KEY: &'static str = "eccc";
+}
+
+impl WeatherProvider for Eccc {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let sites = request_cached(&HttpCacheRequest::new(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &Url::parse(SITE_LIST_URL)?,
+            |body| parse_site_list_csv(&decode_body(body)),
+        ))?;
+
+        let closest_site = find_closest_weather_station(&request.query, &sites)?;
+        trace!("Found closest site {:?}", closest_site);
+
+        let site_data: SiteData = request_cached(&HttpCacheRequest::new_xml_request(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &Url::parse(&format!(
+                "{BASE_URL}/xml/{}/{}_e.xml",
+                closest_site.province, closest_site.code
+            ))?,
+        ))?;
+
+        let conditions = site_data
+            .current_conditions
+            .ok_or_else(|| anyhow!("No current conditions for {}", closest_site.name))?;
+
+        // Prefer the observing station over the city site to report a meaningful distance
+        let (city, coordinates) = conditions.station.map_or_else(
+            || (closest_site.name.clone(), closest_site.coordinates()),
+            |station| {
+                (
+                    station.name,
+                    Coordinates {
+                        latitude: station.latitude,
+                        longitude: station.longitude,
+                    },
+                )
+            },
+        );
+        let distance = calculate_distance(&request.query, &coordinates);
+
+        let wind = conditions.wind.as_ref();
+
+        Ok(Weather {
+            source: SOURCE_URI.into(),
+            location: request.name.clone(),
+            city: Some(city),
+            coordinates,
+            distance: Some(distance),
+            temperature: number(conditions.temperature.as_ref())
+                .map(Celsius::from_f64)
+                .ok_or_else(|| anyhow!("No temperature for {}", closest_site.name))?,
+            relative_humidity: number(conditions.relative_humidity.as_ref()).map(Ratio::Percentage),
+            dew_point: number(conditions.dewpoint.as_ref()).map(Celsius::from_f64),
+            pressure: number(conditions.pressure.as_ref())
+                .map(|v| Kilopascals::from(v).to_hectopascals()),
+            wind_speed: number(wind.and_then(|w| w.speed.as_ref()))
+                .map(|v| KilometersPerHour::from(v).to_meters_per_second()),
+            wind_gust: number(wind.and_then(|w| w.gust.as_ref()))
+                .map(|v| KilometersPerHour::from(v).to_meters_per_second()),
+            wind_direction: number(wind.and_then(|w| w.bearing.as_ref())).map(Degrees::from),
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+
+    fn cache_cardinality(&self) -> usize {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse_site_list_csv {
+        use crate::providers::eccc::{parse_site_list_csv, Site};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_short_list() {
+            assert_eq!(
+                parse_site_list_csv(
+                    "Site Names\n\
+                    Codes,English Names,Province Codes,Latitude,Longitude\n\
+                    s0000458,Toronto,ON,43.74N,79.37W\n\
+                    s0000635,Montr\u{e9}al,QC,45.51N,73.59W\n"
+                )
+                .expect("Parsing works"),
+                vec![
+                    Site {
+                        code: "s0000458".into(),
+                        name: "Toronto".into(),
+                        province: "ON".into(),
+                        latitude: 43.74_f64.into(),
+                        longitude: (-79.37_f64).into(),
+                    },
+                    Site {
+                        code: "s0000635".into(),
+                        name: "Montr\u{e9}al".into(),
+                        province: "QC".into(),
+                        latitude: 45.51_f64.into(),
+                        longitude: (-73.59_f64).into(),
+                    },
+                ]
+            );
+        }
+    }
+
+    mod parse_site_data {
+        use crate::providers::eccc::{number, SiteData};
+        use crate::providers::http_request::xml_deserialize_body;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_current_conditions() {
+            let site_data: SiteData = xml_deserialize_body(
+                &b"<?xml version='1.0' encoding='ISO-8859-1'?>
+<siteData xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">
+  <license>https://dd.weather.gc.ca/doc/LICENCE_GENERAL.txt</license>
+  <location>
+    <continent>North America</continent>
+    <country code=\"ca\">Canada</country>
+    <province code=\"qc\">Qu\xe9bec</province>
+    <name code=\"s0000635\" lat=\"45.51N\" lon=\"73.59W\">Montr\xe9al</name>
+  </location>
+  <currentConditions>
+    <station code=\"yul\" lat=\"45.47N\" lon=\"73.74W\">Montr\xe9al-Trudeau Int'l Airport</station>
+    <condition>Mostly Cloudy</condition>
+    <temperature unitType=\"metric\" units=\"C\">9.4</temperature>
+    <dewpoint unitType=\"metric\" units=\"C\">4.1</dewpoint>
+    <pressure unitType=\"metric\" units=\"kPa\" change=\"0.12\" tendency=\"rising\">101.8</pressure>
+    <visibility unitType=\"metric\" units=\"km\">24.1</visibility>
+    <relativeHumidity units=\"%\">70</relativeHumidity>
+    <wind>
+      <speed unitType=\"metric\" units=\"km/h\">22</speed>
+      <gust unitType=\"metric\" units=\"km/h\"/>
+      <direction>WSW</direction>
+      <bearing units=\"degrees\">248.0</bearing>
+    </wind>
+  </currentConditions>
+</siteData>"
+                    .to_vec(),
+            )
+            .expect("Parsing works");
+
+            let conditions = site_data.current_conditions.expect("Current conditions");
+            let station = conditions.station.expect("Observation station");
+            assert_eq!(station.name, "Montr\u{e9}al-Trudeau Int'l Airport");
+            assert_eq!(station.latitude, 45.47_f64.into());
+            assert_eq!(station.longitude, (-73.74_f64).into());
+            assert_eq!(number(conditions.temperature.as_ref()), Some(9.4_f64));
+            assert_eq!(number(conditions.dewpoint.as_ref()), Some(4.1_f64));
+            assert_eq!(number(conditions.pressure.as_ref()), Some(101.8_f64));
+            assert_eq!(number(conditions.relative_humidity.as_ref()), Some(70_f64));
+
+            let wind = conditions.wind.expect("Wind");
+            assert_eq!(number(wind.speed.as_ref()), Some(22_f64));
+            assert_eq!(number(wind.gust.as_ref()), None);
+            assert_eq!(number(wind.bearing.as_ref()), Some(248_f64));
+        }
+    }
+}
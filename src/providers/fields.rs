@@ -0,0 +1,214 @@
+use crate::providers::units::{
+    Celsius, Coordinates, Degrees, Fahrenheit, Hectopascals, Inches, InchesOfMercury,
+    InchesPerHour, Kelvin, KilometersPerHour, Kilopascals, MetersPerSecond, MilesPerHour,
+    Millimeters, MillimetersPerHour, Pascals, Ratio, ToCelsius, ToHectopascals, ToMetersPerSecond,
+    ToMillimeters, ToMillimetersPerHour,
+};
+use crate::providers::{Measurement, Weather, WeatherRequest};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Weather field values of sources without a fixed schema can be mapped to, in metric units
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Temperature,
+    RelativeHumidity,
+    DewPoint,
+    Pressure,
+    WindSpeed,
+    WindGust,
+    WindDirection,
+    Precipitation,
+    PrecipitationIntensity,
+    UvIndex,
+}
+
+/// Convert a value of a field from its unit of measurement to the metric unit of the field, `None`
+/// if the unit is not supported or measures something else than the field
+pub fn metric_value(field: Field, value: f64, unit: Option<&str>) -> Option<f64> {
+    let Some(symbol) = unit else {
+        return Some(value);
+    };
+
+    Some(match (field, symbol) {
+        (Field::Temperature | Field::DewPoint, "\u{b0}C")
+        | (Field::RelativeHumidity, "%")
+        | (Field::Pressure, "hPa" | "mbar")
+        | (Field::WindSpeed | Field::WindGust, "m/s")
+        | (Field::WindDirection, "\u{b0}")
+        | (Field::Precipitation, "mm")
+        | (Field::PrecipitationIntensity, "mm/h")
+        | (Field::UvIndex, "UV index") => value,
+        (Field::Temperature | Field::DewPoint, "\u{b0}F") => {
+            Fahrenheit::from_f64(value).to_celsius().into()
+        }
+        (Field::Temperature | Field::DewPoint, "K") => Kelvin::from_f64(value).to_celsius().into(),
+        (Field::Pressure, "kPa") => Kilopascals::from(value).to_hectopascals().into(),
+        (Field::Pressure, "Pa") => Pascals::from(value).to_hectopascals().into(),
+        (Field::Pressure, "inHg") => InchesOfMercury::from(value).to_hectopascals().into(),
+        (Field::WindSpeed | Field::WindGust, "km/h") => {
+            KilometersPerHour::from(value).to_meters_per_second().into()
+        }
+        (Field::WindSpeed | Field::WindGust, "mph") => {
+            MilesPerHour::from(value).to_meters_per_second().into()
+        }
+        (Field::Precipitation, "in") => Inches::from(value).to_millimeters().into(),
+        (Field::PrecipitationIntensity, "in/h") => {
+            InchesPerHour::from(value).to_millimeters_per_hour().into()
+        }
+        _ => return None,
+    })
+}
+
+/// Translate unit symbols into metric name suffixes
+pub fn unit_name(symbol: &str) -> Option<String> {
+    let name = match symbol {
+        "" => return None,
+        "\u{b0}C" => "celsius",
+        "\u{b0}F" => "fahrenheit",
+        "\u{b0}" => "degrees",
+        "%" => "ratio",
+        "mm" => "millimeters",
+        "cm" => "centimeters",
+        "m" => "meters",
+        "s" => "seconds",
+        "hPa" => "hectopascals",
+        "kPa" => "kilopascals",
+        "km/h" => "kilometers_per_hour",
+        "m/s" => "meters_per_second",
+        "W/m\u{b2}" => "watts_per_square_meter",
+        "J/kg" => "joules_per_kilogram",
+        "\u{b5}g/m\u{b3}" | "\u{3bc}g/m\u{b3}" => "micrograms_per_cubic_meter",
+        other => {
+            return Some(
+                other
+                    .chars()
+                    .map(|c| match c {
+                        'a'..='z' | 'A'..='Z' | '0'..='9' => c.to_ascii_lowercase(),
+                        _ => '_',
+                    })
+                    .collect::<String>()
+                    .trim_matches('_')
+                    .to_owned(),
+            )
+            .filter(|name| !name.is_empty());
+        }
+    };
+
+    Some(name.into())
+}
+
+/// Export a value under the metric name suffix of its unit, percentages are exported as ratios
+pub fn unit_measurement(name: String, symbol: Option<&str>, value: f64) -> Measurement {
+    Measurement {
+        name,
+        unit: symbol.and_then(unit_name),
+        value: match symbol {
+            Some("%") => value / 100.0_f64,
+            _ => value,
+        },
+    }
+}
+
+/// Extract the number a JSON pointer points to, numeric strings are accepted as well. An empty
+/// pointer selects the whole document
+pub fn pointer_value(document: &Value, pointer: &str) -> anyhow::Result<f64> {
+    let value = document
+        .pointer(pointer)
+        .ok_or_else(|| anyhow!("Document has no value at {pointer:?}"))?;
+
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.trim().parse().ok(),
+        Value::Null | Value::Bool(_) | Value::Array(_) | Value::Object(_) => None,
+    }
+    .ok_or_else(|| anyhow!("Value at {pointer:?} is not a number: {value}"))
+}
+
+/// Map the values of a location to its weather, a temperature is required
+pub fn field_weather(
+    source: &str,
+    request: &WeatherRequest<Coordinates>,
+    values: &BTreeMap<Field, f64>,
+) -> anyhow::Result<Weather> {
+    let value = |field| values.get(&field).copied();
+
+    Ok(Weather {
+        location: request.name.clone(),
+        source: source.into(),
+        coordinates: request.query.clone(),
+        temperature: value(Field::Temperature)
+            .map(Celsius::from_f64)
+            .ok_or_else(|| anyhow!("No temperature available for {}", request.name))?,
+        relative_humidity: value(Field::RelativeHumidity).map(Ratio::Percentage),
+        dew_point: value(Field::DewPoint).map(Celsius::from_f64),
+        pressure: value(Field::Pressure).map(Hectopascals::from),
+        wind_speed: value(Field::WindSpeed).map(MetersPerSecond::from),
+        wind_gust: value(Field::WindGust).map(MetersPerSecond::from),
+        wind_direction: value(Field::WindDirection).map(Degrees::from),
+        precipitation: value(Field::Precipitation).map(Millimeters::from),
+        precipitation_intensity: value(Field::PrecipitationIntensity).map(MillimetersPerHour::from),
+        uv_index: value(Field::UvIndex),
+        ..Weather::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    mod metric_value {
+        use crate::providers::fields::{metric_value, Field};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn convert_matching_units() {
+            assert_eq!(
+                metric_value(Field::Pressure, 1013.0, Some("mbar")),
+                Some(1013.0_f64)
+            );
+            assert_eq!(
+                metric_value(Field::WindGust, 36.0, Some("km/h")),
+                Some(10.0_f64)
+            );
+            assert_eq!(metric_value(Field::UvIndex, 3.0, None), Some(3.0_f64));
+        }
+
+        #[test]
+        fn reject_units_of_other_fields() {
+            assert_eq!(metric_value(Field::Pressure, 50.0, Some("\u{b0}F")), None);
+            assert_eq!(metric_value(Field::Temperature, 81.0, Some("%")), None);
+            assert_eq!(metric_value(Field::Precipitation, 2.0, Some("km")), None);
+        }
+    }
+
+    mod unit_measurement {
+        use crate::providers::fields::{unit_measurement, unit_name};
+        use crate::providers::Measurement;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn export_percentages_as_ratios() {
+            assert_eq!(
+                unit_measurement("cloud_cover_low".into(), Some("%"), 25.0),
+                Measurement {
+                    name: "cloud_cover_low".into(),
+                    unit: Some("ratio".into()),
+                    value: 0.25,
+                }
+            );
+        }
+
+        #[test]
+        fn sanitize_unknown_units() {
+            assert_eq!(unit_name("\u{b0}C").as_deref(), Some("celsius"));
+            assert_eq!(unit_name("wmo code").as_deref(), Some("wmo_code"));
+            assert_eq!(
+                unit_name("\u{3bc}g/m\u{b3}").as_deref(),
+                Some("micrograms_per_cubic_meter")
+            );
+            assert_eq!(unit_name(""), None);
+        }
+    }
+}
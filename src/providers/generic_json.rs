@@ -0,0 +1,461 @@
+use crate::prometheus::is_reserved_metric_name;
+use crate::providers::fields::{
+    field_weather, metric_value, pointer_value, unit_measurement, unit_name, Field,
+};
+use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::Coordinates;
+use crate::providers::{HttpRequestCache, Weather, WeatherProvider, WeatherRequest};
+use anyhow::anyhow;
+use log::debug;
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GenericJson {
+    source: String,
+    url: String,
+    api_key: Option<String>,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    values: Vec<Mapping>,
+    #[serde(flatten)]
+    cache: Configuration,
+}
+
+/// Value of the response a JSON pointer points to, in the given unit. Known weather fields are
+/// converted to metric units, anything else is exported as a measurement
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Mapping {
+    pointer: String,
+    kind: Option<Field>,
+    measurement: Option<String>,
+    unit: Option<String>,
+}
+
+/// Measurement names become part of metric names
+fn is_metric_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_'))
+}
+
+/// Percent-encode everything but unreserved characters, so values can be used in any URL component
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+impl Mapping {
+    fn validate(&self) -> anyhow::Result<()> {
+        match (&self.kind, &self.measurement) {
+            (Some(field), None) => {
+                if metric_value(*field, 0.0, self.unit.as_deref()).is_none() {
+                    return Err(anyhow!(
+                        "Unsupported unit of measurement {} for {field:?} at {:?}",
+                        self.unit.as_deref().unwrap_or_default(),
+                        self.pointer
+                    ));
+                }
+            }
+            (None, Some(measurement)) => {
+                if !is_metric_name(measurement) {
+                    return Err(anyhow!(
+                        "Measurement name {measurement:?} may only contain a-z, 0-9 and _"
+                    ));
+                }
+
+                let unit = match &self.unit {
+                    Some(symbol) => {
+                        Some(unit_name(symbol).ok_or_else(|| anyhow!("Invalid unit {symbol:?}"))?)
+                    }
+                    None => None,
+                };
+
+                if is_reserved_metric_name(measurement, unit.as_deref()) {
+                    return Err(anyhow!(
+                        "Measurement name {measurement:?} is reserved for a built-in metric"
+                    ));
+                }
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Either kind or measurement needs to be set for {:?}",
+                    self.pointer
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn document_weather(
+    source: &str,
+    request: &WeatherRequest<Coordinates>,
+    mappings: &[Mapping],
+    document: &Value,
+) -> anyhow::Result<Weather> {
+    let mut values = BTreeMap::new();
+    let mut measurements = vec![];
+
+    for mapping in mappings {
+        // Responses commonly leave out values that are currently not available
+        let value = match pointer_value(document, &mapping.pointer) {
+            Ok(value) => value,
+            Err(e) => {
+                debug!("Skipping value of {source}: {e}");
+                continue;
+            }
+        };
+
+        match (&mapping.kind, &mapping.measurement) {
+            (Some(field), _) => {
+                let unit = mapping.unit.as_deref();
+                values.insert(
+                    *field,
+                    metric_value(*field, value, unit).ok_or_else(|| {
+                        anyhow!(
+                            "Unsupported unit of measurement {} for {field:?} at {:?}",
+                            unit.unwrap_or_default(),
+                            mapping.pointer
+                        )
+                    })?,
+                );
+            }
+            (None, Some(name)) => {
+                measurements.push(unit_measurement(
+                    name.clone(),
+                    mapping.unit.as_deref(),
+                    value,
+                ));
+            }
+            (None, None) => {
+                return Err(anyhow!(
+                    "Either kind or measurement needs to be set for {:?}",
+                    mapping.pointer
+                ))
+            }
+        }
+    }
+
+    Ok(Weather {
+        measurements,
+        ..field_weather(source, request, &values)?
+    })
+}
+
+fn fill_template(template: &str, coordinates: &Coordinates, key: &str) -> String {
+    template
+        .replace("{latitude}", &coordinates.latitude.to_string())
+        .replace("{longitude}", &coordinates.longitude.to_string())
+        .replace("{api_key}", key)
+}
+
+impl RegisteredProvider for GenericJson {
+    const KEY: &'static str = "generic_json";
+}
+
+impl WeatherProvider for GenericJson {
+    fn id(&self) -> &str {
+        &self.source
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let api_key = self.api_key.as_deref().unwrap_or_default();
+        let url = Url::parse(&fill_template(
+            &self.url,
+            &request.query,
+            &percent_encode(api_key),
+        ))?;
+
+        let mut http_request =
+            HttpCacheRequest::new_json_request(&self.source, client, cache, &Method::GET, &url);
+        for (name, value) in &self.headers {
+            http_request = http_request.with_header(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(&fill_template(value, &request.query, api_key))?,
+            );
+        }
+
+        let document: Value = request_cached(&http_request)?;
+
+        document_weather(&self.source, request, &self.values, &document)
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+
+    fn validate(&self, _location_count: usize) -> anyhow::Result<()> {
+        self.values.iter().try_for_each(Mapping::validate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod document_weather {
+        use crate::providers::generic_json::{
+            document_weather, fill_template, percent_encode, GenericJson,
+        };
+        use crate::providers::units::{Coordinates, Ratio};
+        use crate::providers::{Measurement, WeatherRequest};
+        use pretty_assertions::assert_eq;
+        use serde_json::json;
+
+        fn provider() -> GenericJson {
+            toml::from_str(
+                r#"
+                source = "org.example"
+                url = "https://api.example.org/current?lat={latitude}&lon={longitude}"
+
+                [[values]]
+                pointer = "/current/temp_f"
+                kind = "temperature"
+                unit = "\u00b0F"
+
+                [[values]]
+                pointer = "/current/humidity"
+                kind = "relative_humidity"
+                unit = "%"
+
+                [[values]]
+                pointer = "/current/gust_kph"
+                kind = "wind_gust"
+                unit = "km/h"
+
+                [[values]]
+                pointer = "/air/pm25"
+                measurement = "pm2_5"
+                unit = "\u00b5g/m\u00b3"
+                "#,
+            )
+            .expect("Valid configuration")
+        }
+
+        fn request() -> WeatherRequest<Coordinates> {
+            WeatherRequest {
+                name: "Home".into(),
+                query: Coordinates {
+                    latitude: 52.5_f64.into(),
+                    longitude: 13.4_f64.into(),
+                },
+            }
+        }
+
+        #[test]
+        fn map_document() {
+            let provider = provider();
+            let document = json!({
+                "current": {"temp_f": 212_u16, "humidity": "81"},
+                "air": {"pm25": 7.5_f64}
+            });
+
+            let weather =
+                document_weather(&provider.source, &request(), &provider.values, &document)
+                    .expect("Mapping works");
+
+            assert_eq!(weather.source, "org.example");
+            assert_eq!(
+                Some(f64::from(weather.temperature).round()),
+                Some(100.0_f64)
+            );
+            assert_eq!(weather.relative_humidity, Some(Ratio::Percentage(81.0)));
+            assert_eq!(weather.wind_gust, None);
+            assert_eq!(
+                weather.measurements,
+                vec![Measurement {
+                    name: "pm2_5".into(),
+                    unit: Some("micrograms_per_cubic_meter".into()),
+                    value: 7.5,
+                }]
+            );
+        }
+
+        #[test]
+        fn fail_on_unsupported_unit() {
+            let mut provider = provider();
+            if let Some(mapping) = provider.values.first_mut() {
+                mapping.unit = Some("Rankine".into());
+            }
+
+            let error = document_weather(
+                &provider.source,
+                &request(),
+                &provider.values,
+                &json!({"current": {"temp_f": 50_u16}}),
+            )
+            .expect_err("Mapping fails");
+
+            assert_eq!(
+                error.to_string(),
+                r#"Unsupported unit of measurement Rankine for Temperature at "/current/temp_f""#
+            );
+        }
+
+        #[test]
+        fn substitute_placeholders() {
+            assert_eq!(
+                fill_template(
+                    "https://api.example.org/?q={latitude},{longitude}&key={api_key}",
+                    &request().query,
+                    &percent_encode("s3cr3t&x=1")
+                ),
+                "https://api.example.org/?q=52.5000000,13.4000000&key=s3cr3t%26x%3D1"
+            );
+        }
+    }
+    mod validate {
+        use crate::providers::generic_json::GenericJson;
+        use crate::providers::WeatherProvider;
+        use pretty_assertions::assert_eq;
+        use toml::de::Error;
+
+        fn provider(value: &str) -> Result<GenericJson, Error> {
+            toml::from_str(&format!(
+                r#"
+                source = "org.example"
+                url = "https://api.example.org/current"
+                values = [{{ pointer = "/value", {value} }}]
+                "#
+            ))
+        }
+
+        #[test]
+        fn accept_valid_mappings() {
+            for value in [
+                r#"kind = "temperature", unit = "K""#,
+                r#"measurement = "pm2_5", unit = "\u00b5g/m\u00b3""#,
+                r#"measurement = "lightning_strikes""#,
+            ] {
+                assert!(
+                    provider(value)
+                        .expect("Valid configuration")
+                        .validate(1)
+                        .is_ok(),
+                    "{value} is valid"
+                );
+            }
+        }
+
+        #[test]
+        fn reject_misspelled_kind() {
+            assert!(
+                provider(r#"kind = "temprature""#).is_err(),
+                "Unknown kinds are rejected"
+            );
+        }
+
+        #[test]
+        fn reject_invalid_mappings() {
+            for (value, message) in [
+                (
+                    r#"measurement = "pm2.5""#,
+                    r#"Measurement name "pm2.5" may only contain a-z, 0-9 and _"#,
+                ),
+                (
+                    r#"measurement = "pm2_5", unit = "/""#,
+                    r#"Invalid unit "/""#,
+                ),
+                (
+                    r#"measurement = "temperature", unit = "\u00b0C""#,
+                    r#"Measurement name "temperature" is reserved for a built-in metric"#,
+                ),
+                (
+                    r#"measurement = "wind_gust", unit = "speed meters per second""#,
+                    r#"Measurement name "wind_gust" is reserved for a built-in metric"#,
+                ),
+                (
+                    r#"kind = "temperature", unit = "Rankine""#,
+                    r#"Unsupported unit of measurement Rankine for Temperature at "/value""#,
+                ),
+                (
+                    r#"kind = "pressure", unit = "\u00b0F""#,
+                    "Unsupported unit of measurement \u{b0}F for Pressure at \"/value\"",
+                ),
+                (
+                    r#"kind = "temperature", measurement = "temperature""#,
+                    r#"Either kind or measurement needs to be set for "/value""#,
+                ),
+            ] {
+                let error = provider(value)
+                    .expect("Valid configuration")
+                    .validate(1)
+                    .expect_err("Validation fails");
+
+                assert_eq!(error.to_string(), message);
+            }
+        }
+    }
+
+    #[allow(
+        clippy::significant_drop_tightening,
+        reason = "Mock servers need to outlive the requests sent to them"
+    )]
+    mod for_coordinates {
+        use crate::providers::generic_json::GenericJson;
+        use crate::providers::units::Coordinates;
+        use crate::providers::{HttpRequestCache, WeatherProvider, WeatherRequest};
+        use mockito::{Matcher, Server};
+        use pretty_assertions::assert_eq;
+        use reqwest::blocking::Client;
+
+        #[test]
+        fn send_templated_request() {
+            let mut server = Server::new();
+            let current = server
+                .mock("GET", "/current")
+                .match_query(Matcher::AllOf(vec![
+                    Matcher::UrlEncoded("lat".into(), "52.5000000".into()),
+                    Matcher::UrlEncoded("lon".into(), "13.4000000".into()),
+                ]))
+                .match_header("x-api-key", "secret")
+                .with_body(r#"{"temperature": 11.5}"#)
+                .create();
+            let provider: GenericJson = toml::from_str(&format!(
+                r#"
+                source = "org.example"
+                url = "{}/current?lat={{latitude}}&lon={{longitude}}"
+                api_key = "secret"
+                headers = {{ X-Api-Key = "{{api_key}}" }}
+                values = [{{ pointer = "/temperature", kind = "temperature" }}]
+                "#,
+                server.url()
+            ))
+            .expect("Valid configuration");
+            let request = WeatherRequest {
+                name: "Home".into(),
+                query: Coordinates {
+                    latitude: 52.5_f64.into(),
+                    longitude: 13.4_f64.into(),
+                },
+            };
+
+            let weather = provider
+                .for_coordinates(&Client::new(), &HttpRequestCache::new(1), &request)
+                .expect("Weather is fetched");
+
+            current.assert();
+            assert_eq!(provider.id(), "org.example");
+            assert_eq!(Some(f64::from(weather.temperature)), Some(11.5_f64));
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::station_lookup::{find_closest_weather_station, Station};
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, Hectopascals, MetersPerSecond, Ratio,
+};
+use crate::providers::{
+    calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::anyhow;
+use log::trace;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const SOURCE_URI: &str = "at.geosphere";
+const BASE_URL: &str = "https://dataset.api.hub.geosphere.at/v1/station/current/tawes-v1-10min";
+const PARAMETERS: &str = "TL,RF,TP,P,FF,FFX,DD";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GeosphereAustria {
+    #[serde(flatten)]
+    cache: Configuration,
+}
+
+#[derive(Deserialize, Debug)]
+struct TawesMetadata {
+    stations: Vec<WeatherStation>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct WeatherStation {
+    id: String,
+    name: String,
+    #[serde(rename = "lat")]
+    latitude: Coordinate,
+    #[serde(rename = "lon")]
+    longitude: Coordinate,
+    is_active: bool,
+}
+
+impl Station for WeatherStation {
+    fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude.clone(),
+            longitude: self.longitude.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct TawesObservations {
+    features: Vec<TawesFeature>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TawesFeature {
+    properties: TawesProperties,
+}
+
+#[derive(Deserialize, Debug)]
+struct TawesProperties {
+    parameters: HashMap<String, TawesParameter>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TawesParameter {
+    data: Vec<Option<f64>>,
+}
+
+impl TawesProperties {
+    // The current endpoint returns a single timestamp, but use the latest reported value to be safe
+    fn latest(&self, parameter: &str) -> Option<f64> {
+        self.parameters
+            .get(parameter)
+            .and_then(|p| p.data.iter().rev().find_map(|value| *value))
+    }
+}
+
+impl RegisteredProvider for GeosphereAustria {
+    const KEY: &'static str = "geosphere_austria";
+}
+
+impl WeatherProvider for GeosphereAustria {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let metadata: TawesMetadata = request_cached(&HttpCacheRequest::new_json_request(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &Url::parse(&format!("{BASE_URL}/metadata"))?,
+        ))?;
+
+        let active_stations: Vec<WeatherStation> = metadata
+            .stations
+            .into_iter()
+            .filter(|station| station.is_active)
+            .collect();
+
+        let closest_station = find_closest_weather_station(&request.query, &active_stations)?;
+        trace!("Found closest weather station {:?}", closest_station);
+
+        let observations_url = Url::parse_with_params(
+            BASE_URL,
+            &[
+                ("parameters", PARAMETERS),
+                ("station_ids", &closest_station.id),
+            ],
+        )?;
+
+        let observations: TawesObservations = request_cached(&HttpCacheRequest::new_json_request(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &observations_url,
+        ))?;
+
+        let properties = &observations
+            .features
+            .first()
+            .ok_or_else(|| anyhow!("No observations for station {}", closest_station.name))?
+            .properties;
+
+        let coordinates = closest_station.coordinates();
+        let distance = calculate_distance(&request.query, &coordinates);
+
+        Ok(Weather {
+            source: SOURCE_URI.into(),
+            location: request.name.clone(),
+            city: Some(closest_station.name.clone()),
+            coordinates,
+            distance: Some(distance),
+            temperature: properties
+                .latest("TL")
+                .map(Celsius::from_f64)
+                .ok_or_else(|| anyhow!("No temperature for station {}", closest_station.name))?,
+            relative_humidity: properties.latest("RF").map(Ratio::Percentage),
+            dew_point: properties.latest("TP").map(Celsius::from_f64),
+            pressure: properties.latest("P").map(Hectopascals::from),
+            wind_speed: properties.latest("FF").map(MetersPerSecond::from),
+            wind_gust: properties.latest("FFX").map(MetersPerSecond::from),
+            wind_direction: properties.latest("DD").map(Degrees::from),
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+
+    fn cache_cardinality(&self) -> usize {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse_response {
+        use crate::providers::geosphere_austria::{
+            TawesMetadata, TawesObservations, WeatherStation,
+        };
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_metadata() {
+            let metadata: TawesMetadata = serde_json::from_str(
+                r#"{
+                    "title": "TAWES 10 Minuten Messwerte",
+                    "parameters": [{"name": "TL", "long_name": "Lufttemperatur", "unit": "\u00b0C"}],
+                    "stations": [
+                        {"type": "COMBINED", "id": "11035", "group_id": null, "name": "WIEN/HOHE WARTE", "state": "Wien", "lat": 48.248611, "lon": 16.356389, "altitude": 198.0, "valid_from": "1872-01-01T00:00+00:00", "valid_to": "2100-12-31T00:00+00:00", "has_sunshine": true, "has_global_radiation": true, "is_active": true},
+                        {"type": "COMBINED", "id": "11010", "group_id": null, "name": "LINZ/STADT", "state": "Ober\u00f6sterreich", "lat": 48.296667, "lon": 14.286667, "altitude": 262.0, "valid_from": "1991-01-01T00:00+00:00", "valid_to": "2016-06-30T00:00+00:00", "has_sunshine": true, "has_global_radiation": false, "is_active": false}
+                    ]
+                }"#,
+            )
+            .expect("Parsing works");
+
+            assert_eq!(
+                metadata.stations,
+                vec![
+                    WeatherStation {
+                        id: "11035".into(),
+                        name: "WIEN/HOHE WARTE".into(),
+                        latitude: 48.248_611_f64.into(),
+                        longitude: 16.356_389_f64.into(),
+                        is_active: true,
+                    },
+                    WeatherStation {
+                        id: "11010".into(),
+                        name: "LINZ/STADT".into(),
+                        latitude: 48.296_667_f64.into(),
+                        longitude: 14.286_667_f64.into(),
+                        is_active: false,
+                    }
+                ]
+            );
+        }
+
+        #[test]
+        fn parse_observations() {
+            let observations: TawesObservations = serde_json::from_str(
+                r#"{
+                    "media_type": "application/json",
+                    "type": "FeatureCollection",
+                    "version": "v1",
+                    "timestamps": ["2026-10-18T10:00+00:00"],
+                    "features": [{
+                        "type": "Feature",
+                        "geometry": {"type": "Point", "coordinates": [16.356389, 48.248611]},
+                        "properties": {
+                            "parameters": {
+                                "TL": {"name": "Lufttemperatur", "unit": "\u00b0C", "data": [12.4]},
+                                "RF": {"name": "Relative Feuchte", "unit": "%", "data": [71.0]},
+                                "P": {"name": "Luftdruck", "unit": "hPa", "data": [991.2]},
+                                "FF": {"name": "Windgeschwindigkeit", "unit": "m/s", "data": [null]}
+                            },
+                            "station": "11035"
+                        }
+                    }]
+                }"#,
+            )
+            .expect("Parsing works");
+
+            let properties = &observations
+                .features
+                .first()
+                .expect("One feature")
+                .properties;
+            assert_eq!(properties.latest("TL"), Some(12.4_f64));
+            assert_eq!(properties.latest("RF"), Some(71_f64));
+            assert_eq!(properties.latest("P"), Some(991.2_f64));
+            assert_eq!(properties.latest("FF"), None);
+            assert_eq!(properties.latest("DD"), None);
+        }
+    }
+}
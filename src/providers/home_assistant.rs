@@ -0,0 +1,336 @@
+use crate::providers::fields::{field_weather, metric_value, Field};
+use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::Coordinates;
+use crate::providers::{HttpRequestCache, Weather, WeatherProvider, WeatherRequest};
+use anyhow::anyhow;
+use log::warn;
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+const SOURCE_URI: &str = "io.home-assistant";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HomeAssistant {
+    base_url: String,
+    token: String,
+    sensors: Vec<Sensors>,
+    #[serde(flatten)]
+    cache: Configuration,
+}
+
+/// Entities providing the weather fields of a location
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Sensors {
+    location: String,
+    entities: BTreeMap<Field, String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EntityState {
+    entity_id: String,
+    state: String,
+    attributes: Attributes,
+}
+
+#[derive(Deserialize, Debug)]
+struct Attributes {
+    unit_of_measurement: Option<String>,
+}
+
+/// Convert the state of an entity from its unit of measurement to the metric unit of the field it is
+/// mapped to, entities measuring something else than the field are rejected
+fn entity_value(field: Field, entity: &EntityState) -> anyhow::Result<f64> {
+    let value: f64 = entity.state.parse().map_err(|e| {
+        anyhow!(
+            "Entity {} has no numeric state {}: {e}",
+            entity.entity_id,
+            entity.state
+        )
+    })?;
+
+    let unit = entity.attributes.unit_of_measurement.as_deref();
+
+    metric_value(field, value, unit).ok_or_else(|| {
+        anyhow!(
+            "Entity {} has unsupported unit of measurement {} for {field:?}",
+            entity.entity_id,
+            unit.unwrap_or_default()
+        )
+    })
+}
+
+impl HomeAssistant {
+    fn entity_state(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        entity_id: &str,
+    ) -> anyhow::Result<EntityState> {
+        let mut url = Url::parse(&self.base_url)?;
+        url.path_segments_mut()
+            .map_err(|()| anyhow!("Base URL {} cannot be used as a base", self.base_url))?
+            .pop_if_empty()
+            .extend(&["api", "states", entity_id]);
+
+        request_cached(
+            &HttpCacheRequest::new_json_request(SOURCE_URI, client, cache, &Method::GET, &url)
+                .with_header(
+                    AUTHORIZATION,
+                    HeaderValue::from_str(&format!("Bearer {}", self.token))?,
+                ),
+        )
+    }
+}
+
+impl RegisteredProvider for HomeAssistant {
+    const KEY: &'static str = "home_assistant";
+}
+
+impl WeatherProvider for HomeAssistant {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let sensors = self
+            .sensors
+            .iter()
+            .find(|sensors| sensors.location == request.name)
+            .ok_or_else(|| anyhow!("No Home Assistant sensors configured for {}", request.name))?;
+
+        // Sensors regularly become unavailable, which only leaves out their field
+        let values = sensors
+            .entities
+            .iter()
+            .filter_map(|(field, entity_id)| {
+                self.entity_state(client, cache, entity_id)
+                    .and_then(|entity| entity_value(*field, &entity))
+                    .map_err(|e| warn!("Could not read Home Assistant entity {entity_id}: {e}"))
+                    .ok()
+                    .map(|value| (*field, value))
+            })
+            .collect();
+
+        field_weather(SOURCE_URI, request, &values)
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+
+    fn cache_cardinality(&self) -> usize {
+        // Every entity of a location is requested separately
+        self.sensors
+            .iter()
+            .map(|sensors| sensors.entities.len())
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn serves(&self, location: &str) -> bool {
+        self.sensors
+            .iter()
+            .any(|sensors| sensors.location == location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod entity_value {
+        use crate::providers::fields::Field;
+        use crate::providers::home_assistant::{entity_value, EntityState};
+        use pretty_assertions::assert_eq;
+
+        fn entity(state: &str, unit: Option<&str>) -> EntityState {
+            serde_json::from_value(serde_json::json!({
+                "entity_id": "sensor.outdoor",
+                "state": state,
+                "attributes": {"unit_of_measurement": unit, "friendly_name": "Outdoor"},
+                "last_changed": "2026-10-18T10:00:00+00:00"
+            }))
+            .expect("Parsing works")
+        }
+
+        #[test]
+        fn keep_metric_units() {
+            assert_eq!(
+                entity_value(Field::Temperature, &entity("11.5", Some("\u{b0}C"))).ok(),
+                Some(11.5_f64)
+            );
+            assert_eq!(
+                entity_value(Field::RelativeHumidity, &entity("81", Some("%"))).ok(),
+                Some(81.0_f64)
+            );
+            assert_eq!(
+                entity_value(Field::UvIndex, &entity("3", None)).ok(),
+                Some(3.0_f64)
+            );
+        }
+
+        #[test]
+        fn convert_imperial_units() {
+            assert_eq!(
+                entity_value(Field::Temperature, &entity("212", Some("\u{b0}F")))
+                    .ok()
+                    .map(f64::round),
+                Some(100.0_f64)
+            );
+            assert_eq!(
+                entity_value(Field::WindGust, &entity("36", Some("km/h"))).ok(),
+                Some(10.0_f64)
+            );
+            assert_eq!(
+                entity_value(Field::Precipitation, &entity("0.5", Some("in"))).ok(),
+                Some(12.7_f64)
+            );
+        }
+
+        #[test]
+        fn fail_on_unavailable_entity() {
+            let error = entity_value(Field::Temperature, &entity("unavailable", Some("\u{b0}C")))
+                .expect_err("Conversion fails");
+
+            assert_eq!(
+                error.to_string(),
+                "Entity sensor.outdoor has no numeric state unavailable: invalid float literal"
+            );
+        }
+
+        #[test]
+        fn fail_on_unsupported_unit() {
+            let error = entity_value(Field::WindSpeed, &entity("2", Some("Bft")))
+                .expect_err("Conversion fails");
+
+            assert_eq!(
+                error.to_string(),
+                "Entity sensor.outdoor has unsupported unit of measurement Bft for WindSpeed"
+            );
+        }
+
+        #[test]
+        fn fail_on_unit_of_other_field() {
+            for unit in ["%", "km"] {
+                let error = entity_value(Field::Temperature, &entity("81", Some(unit)))
+                    .expect_err("Conversion fails");
+
+                assert_eq!(
+                    error.to_string(),
+                    format!(
+                        "Entity sensor.outdoor has unsupported unit of measurement {unit} for Temperature"
+                    )
+                );
+            }
+        }
+    }
+
+    #[allow(
+        clippy::significant_drop_tightening,
+        reason = "Mock servers need to outlive the requests sent to them"
+    )]
+    mod for_coordinates {
+        use crate::providers::home_assistant::HomeAssistant;
+        use crate::providers::units::{Coordinates, Ratio};
+        use crate::providers::{HttpRequestCache, WeatherProvider, WeatherRequest};
+        use mockito::Server;
+        use pretty_assertions::assert_eq;
+        use reqwest::blocking::Client;
+
+        fn provider(base_url: &str) -> HomeAssistant {
+            toml::from_str(&format!(
+                r#"
+                base_url = "{base_url}"
+                token = "long-lived"
+
+                [[sensors]]
+                location = "Garden"
+                entities = {{ temperature = "sensor.garden_temperature", relative_humidity = "sensor.garden_humidity", pressure = "sensor.garden_pressure" }}
+                "#
+            ))
+            .expect("Valid configuration")
+        }
+
+        fn request(name: &str) -> WeatherRequest<Coordinates> {
+            WeatherRequest {
+                name: name.into(),
+                query: Coordinates {
+                    latitude: 52.5_f64.into(),
+                    longitude: 13.4_f64.into(),
+                },
+            }
+        }
+
+        #[test]
+        fn map_entity_states() {
+            let mut server = Server::new();
+            let temperature = server
+                .mock("GET", "/api/states/sensor.garden_temperature")
+                .match_header("authorization", "Bearer long-lived")
+                .with_body(r#"{"entity_id": "sensor.garden_temperature", "state": "50", "attributes": {"unit_of_measurement": "\u00b0F"}}"#)
+                .create();
+            let humidity = server
+                .mock("GET", "/api/states/sensor.garden_humidity")
+                .match_header("authorization", "Bearer long-lived")
+                .with_body(r#"{"entity_id": "sensor.garden_humidity", "state": "81", "attributes": {"unit_of_measurement": "%"}}"#)
+                .create();
+            let pressure = server
+                .mock("GET", "/api/states/sensor.garden_pressure")
+                .with_body(r#"{"entity_id": "sensor.garden_pressure", "state": "unavailable", "attributes": {}}"#)
+                .create();
+
+            let weather = provider(&server.url())
+                .for_coordinates(
+                    &Client::new(),
+                    &HttpRequestCache::new(1),
+                    &request("Garden"),
+                )
+                .expect("Weather is fetched");
+
+            temperature.assert();
+            humidity.assert();
+            pressure.assert();
+            assert_eq!(weather.source, "io.home-assistant");
+            assert_eq!(Some(f64::from(weather.temperature).round()), Some(10.0_f64));
+            assert_eq!(weather.relative_humidity, Some(Ratio::Percentage(81.0)));
+            assert_eq!(weather.pressure, None);
+        }
+
+        #[test]
+        fn cache_every_entity() {
+            assert_eq!(provider("http://localhost:8123").cache_cardinality(), 3);
+        }
+
+        #[test]
+        fn fail_for_unconfigured_location() {
+            let error = provider("http://localhost:8123")
+                .for_coordinates(
+                    &Client::new(),
+                    &HttpRequestCache::new(1),
+                    &request("Office"),
+                )
+                .expect_err("Location is not configured");
+
+            assert_eq!(
+                error.to_string(),
+                "No Home Assistant sensors configured for Office"
+            );
+        }
+
+        #[test]
+        fn serve_configured_locations() {
+            let provider = provider("http://localhost:8123");
+
+            assert!(provider.serves("Garden"), "Location is served");
+            assert!(!provider.serves("Office"), "Location is not served");
+        }
+    }
+}
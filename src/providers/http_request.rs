@@ -1,13 +1,14 @@
 use crate::providers::HttpRequestCache;
 use anyhow::anyhow;
-use derive_more::Constructor;
 use failsafe::backoff::{exponential, Exponential};
 use failsafe::failure_policy::{consecutive_failures, ConsecutiveFailures};
 use failsafe::{CircuitBreaker, Config, Error, StateMachine};
 use log::{debug, trace};
 use moka::sync::Cache as MokaCache;
 use once_cell::sync::Lazy;
+use quick_xml::de;
 use reqwest::blocking::{Client, Response};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::{Method, Url};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -29,7 +30,6 @@ const fn default_refresh_interval() -> Duration {
     Duration::from_secs(60 * 10)
 }
 
-#[derive(Constructor)]
 pub struct HttpCacheRequest<'req, R: Debug = String> {
     source: &'req str,
     client: &'req Client,
@@ -37,6 +37,7 @@ pub struct HttpCacheRequest<'req, R: Debug = String> {
     method: &'req Method,
     url: &'req Url,
     deserialize: fn(body: &Vec<u8>) -> anyhow::Result<R>,
+    headers: HeaderMap,
 }
 
 const CONSECUTIVE_FAILURE_COUNT: u32 = 3;
@@ -48,6 +49,35 @@ type HttpCircuitBreaker = StateMachine<ConsecutiveFailures<Exponential>, ()>;
 static CIRCUIT_BREAKER_REGISTRY: Lazy<RwLock<HashMap<String, HttpCircuitBreaker>>> =
     Lazy::new(|| RwLock::new(HashMap::new()));
 
+impl<'req, R: Debug> HttpCacheRequest<'req, R> {
+    pub fn new(
+        source: &'req str,
+        client: &'req Client,
+        cache: &'req HttpRequestCache,
+        method: &'req Method,
+        url: &'req Url,
+        deserialize: fn(body: &Vec<u8>) -> anyhow::Result<R>,
+    ) -> Self {
+        Self {
+            source,
+            client,
+            cache,
+            method,
+            url,
+            deserialize,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Send an additional header with the request. Headers are not part of the cache key and their
+    /// values are marked as sensitive, so they never show up in debug output
+    pub fn with_header(mut self, name: HeaderName, mut value: HeaderValue) -> Self {
+        value.set_sensitive(true);
+        self.headers.insert(name, value);
+        self
+    }
+}
+
 impl HttpCacheRequest<'_> {
     pub fn new_json_request<'req, T: Debug + DeserializeOwned>(
         source: &'req str,
@@ -58,6 +88,16 @@ impl HttpCacheRequest<'_> {
     ) -> HttpCacheRequest<'req, T> {
         HttpCacheRequest::new(source, client, cache, method, url, serde_deserialize_body)
     }
+
+    pub fn new_xml_request<'req, T: Debug + DeserializeOwned>(
+        source: &'req str,
+        client: &'req Client,
+        cache: &'req HttpRequestCache,
+        method: &'req Method,
+        url: &'req Url,
+    ) -> HttpCacheRequest<'req, T> {
+        HttpCacheRequest::new(source, client, cache, method, url, xml_deserialize_body)
+    }
 }
 
 fn serde_deserialize_body<T: Debug + DeserializeOwned>(body: &Vec<u8>) -> anyhow::Result<T> {
@@ -65,10 +105,27 @@ fn serde_deserialize_body<T: Debug + DeserializeOwned>(body: &Vec<u8>) -> anyhow
     Ok(serde_json::from_slice(body)?)
 }
 
+pub(in crate::providers) fn xml_deserialize_body<T: Debug + DeserializeOwned>(
+    body: &Vec<u8>,
+) -> anyhow::Result<T> {
+    trace!("Deserializing XML body {body:?}");
+    Ok(de::from_reader(body.as_slice())?)
+}
+
+pub(in crate::providers) fn decode_latin1_body(body: &[u8]) -> String {
+    body.iter()
+        .filter_map(|&c| char::from_u32(c.into()))
+        .collect()
+}
+
+fn cache_key<R: Debug>(request: &HttpCacheRequest<R>) -> (Method, Url) {
+    (request.method.clone(), request.url.clone())
+}
+
 pub(in crate::providers) fn request_cached<R: Debug>(
     request: &HttpCacheRequest<R>,
 ) -> anyhow::Result<R> {
-    let key = (request.method.clone(), request.url.clone());
+    let key = cache_key(request);
 
     let value = request.cache.try_get_with_by_ref(&key, || {
         debug!(
@@ -83,10 +140,11 @@ pub(in crate::providers) fn request_cached<R: Debug>(
                 .unwrap_or(Duration::from_secs(0))
         );
 
-        let circuit_breaker_scope = request
+        let host = request
             .url
             .host_str()
             .ok_or_else(|| anyhow!("Could not extract host from URL"))?;
+        let circuit_breaker_scope = &circuit_breaker_scope(request.cache, host);
 
         // Separate scope so read lock is dropped at the end if circuit breaker does not yet exist
         {
@@ -137,6 +195,13 @@ pub(in crate::providers) fn request_cached<R: Debug>(
     }
 }
 
+/// Circuit breakers are scoped by host, and additionally by instance for named provider instances
+fn circuit_breaker_scope(cache: &HttpRequestCache, host: &str) -> String {
+    cache
+        .name()
+        .map_or_else(|| host.to_owned(), |name| format!("{name}@{host}"))
+}
+
 fn ensure_circuit_breaker(circuit_breaker_scope: &str) {
     trace!(
         "Trying to acquire write lock to instantiate circuit breaker {}",
@@ -203,6 +268,7 @@ fn request_url<R: Debug>(request: &HttpCacheRequest<R>) -> anyhow::Result<Respon
     let response = request
         .client
         .request(request.method.clone(), request.url.clone())
+        .headers(request.headers.clone())
         .send()?;
 
     if !response.status().is_success() {
@@ -215,3 +281,38 @@ fn request_url<R: Debug>(request: &HttpCacheRequest<R>) -> anyhow::Result<Respon
 
     Ok(response)
 }
+
+#[cfg(test)]
+mod tests {
+    mod cache_key {
+        use crate::providers::http_request::{cache_key, HttpCacheRequest};
+        use crate::providers::HttpRequestCache;
+        use pretty_assertions::assert_eq;
+        use reqwest::blocking::Client;
+        use reqwest::header::{HeaderName, HeaderValue};
+        use reqwest::{Method, Url};
+
+        #[test]
+        fn ignore_headers() {
+            let client = Client::new();
+            let cache = HttpRequestCache::new(1);
+            let url = Url::parse("https://example.org/forecast?lat=1&lon=2").expect("Valid URL");
+
+            let without_header: HttpCacheRequest =
+                HttpCacheRequest::new_json_request("test", &client, &cache, &Method::GET, &url);
+            let with_header: HttpCacheRequest =
+                HttpCacheRequest::new_json_request("test", &client, &cache, &Method::GET, &url)
+                    .with_header(
+                        HeaderName::from_static("apikey"),
+                        HeaderValue::from_static("secret"),
+                    );
+
+            assert_eq!(cache_key(&with_header), cache_key(&without_header));
+            assert_eq!(cache_key(&with_header), (Method::GET, url.clone()));
+            assert!(
+                !format!("{:?}", with_header.headers).contains("secret"),
+                "Header value must not be logged"
+            );
+        }
+    }
+}
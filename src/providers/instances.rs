@@ -0,0 +1,240 @@
+use crate::providers::units::Coordinates;
+use crate::providers::{HttpRequestCache, Weather, WeatherProvider, WeatherRequest};
+use reqwest::blocking::Client;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+use toml::Value;
+
+/// Configuration of a provider type, either as a single unnamed provider (`[provider.open_meteo]`)
+/// or as several named instances (`[provider.open_meteo.instances.icon]`,
+/// `[provider.open_meteo.instances.gfs]`)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Instances<T> {
+    Single(T),
+    Named(BTreeMap<String, T>),
+}
+
+const INSTANCES_KEY: &str = "instances";
+
+/// Named instances of a provider, which are configured in the `instances` table
+fn named_instances(value: &Value) -> Result<Option<&Value>, String> {
+    let Some(table) = value.as_table() else {
+        return Ok(None);
+    };
+
+    match table.get(INSTANCES_KEY) {
+        Some(instances) if table.len() == 1 => Ok(Some(instances)),
+        Some(_) => Err(format!(
+            "{INSTANCES_KEY} cannot be combined with other options of the provider"
+        )),
+        // Providers do not consist of tables only, so these are instances without the marker
+        None if !table.is_empty() && table.values().all(Value::is_table) => Err(format!(
+            "named instances need to be configured as [provider.<type>.{INSTANCES_KEY}.<name>]"
+        )),
+        None => Ok(None),
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Instances<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+
+        let Some(instances) = named_instances(&value).map_err(D::Error::custom)? else {
+            return T::deserialize(value)
+                .map(Self::Single)
+                .map_err(D::Error::custom);
+        };
+
+        BTreeMap::<String, Value>::deserialize(instances.clone())
+            .map_err(D::Error::custom)?
+            .into_iter()
+            .map(|(name, instance)| {
+                T::deserialize(instance)
+                    .map(|provider| (name.clone(), provider))
+                    .map_err(|e| D::Error::custom(format!("instance {name}: {e}")))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self::Named)
+    }
+}
+
+impl<T: WeatherProvider + Send + Sync + 'static> Instances<T> {
+    /// Create the configured providers, a single configuration may expand into several providers
+    pub fn into_providers(
+        self,
+        expand: fn(T) -> Vec<T>,
+    ) -> Vec<Arc<dyn WeatherProvider + Send + Sync>> {
+        match self {
+            Self::Single(provider) => expand(provider)
+                .into_iter()
+                .map(|p| -> Arc<dyn WeatherProvider + Send + Sync> { Arc::new(p) })
+                .collect(),
+            Self::Named(instances) => instances
+                .into_iter()
+                .flat_map(|(name, provider)| {
+                    expand(provider).into_iter().map(
+                        move |p| -> Arc<dyn WeatherProvider + Send + Sync> {
+                            Arc::new(NamedInstance {
+                                name: name.clone(),
+                                provider: p,
+                            })
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A named instance of a provider, which tags its weather with the instance name
+#[derive(Debug)]
+struct NamedInstance<T> {
+    name: String,
+    provider: T,
+}
+
+impl<T: WeatherProvider> WeatherProvider for NamedInstance<T> {
+    fn id(&self) -> &str {
+        self.provider.id()
+    }
+
+    fn instance(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        self.provider
+            .for_coordinates(client, cache, request)
+            .map(|weather| Weather {
+                instance: Some(self.name.clone()),
+                ..weather
+            })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.provider.refresh_interval()
+    }
+
+    fn cache_cardinality(&self) -> usize {
+        self.provider.cache_cardinality()
+    }
+
+    fn serves(&self, location: &str) -> bool {
+        self.provider.serves(location)
+    }
+
+    fn validate(&self, location_count: usize) -> anyhow::Result<()> {
+        self.provider.validate(location_count)
+    }
+
+    fn start(&self) -> anyhow::Result<()> {
+        self.provider.start()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod deserialize {
+        use crate::providers::instances::Instances;
+        use pretty_assertions::assert_eq;
+        use serde::Deserialize;
+        use std::collections::BTreeMap;
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Example {
+            api_key: Option<String>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Providers {
+            example: Instances<Example>,
+        }
+
+        fn parse(config: &str) -> Instances<Example> {
+            toml::from_str::<Providers>(config)
+                .expect("Valid configuration")
+                .example
+        }
+
+        #[test]
+        fn single() {
+            assert_eq!(
+                parse("[example]\napi_key = \"abc\""),
+                Instances::Single(Example {
+                    api_key: Some("abc".into())
+                })
+            );
+        }
+
+        #[test]
+        fn single_without_options() {
+            assert_eq!(
+                parse("[example]"),
+                Instances::Single(Example { api_key: None })
+            );
+        }
+
+        #[test]
+        fn named() {
+            assert_eq!(
+                parse(
+                    "[example.instances.free]\napi_key = \"abc\"\n[example.instances.paid]\napi_key = \"def\""
+                ),
+                Instances::Named(BTreeMap::from([
+                    (
+                        "free".into(),
+                        Example {
+                            api_key: Some("abc".into())
+                        }
+                    ),
+                    (
+                        "paid".into(),
+                        Example {
+                            api_key: Some("def".into())
+                        }
+                    ),
+                ]))
+            );
+        }
+
+        #[test]
+        fn reject_instances_without_marker() {
+            let error = toml::from_str::<Providers>(
+                "[example.free]\napi_key = \"abc\"\n[example.paid]\napi_key = \"def\"",
+            )
+            .expect_err("Configuration is ambiguous");
+
+            assert!(
+                error.to_string().contains(
+                    "named instances need to be configured as [provider.<type>.instances.<name>]"
+                ),
+                "Unexpected error {error}"
+            );
+        }
+
+        #[test]
+        fn reject_instances_with_other_options() {
+            let error = toml::from_str::<Providers>(
+                "[example]\napi_key = \"abc\"\n[example.instances.paid]\napi_key = \"def\"",
+            )
+            .expect_err("Configuration is ambiguous");
+
+            assert!(
+                error
+                    .to_string()
+                    .contains("instances cannot be combined with other options of the provider"),
+                "Unexpected error {error}"
+            );
+        }
+    }
+}
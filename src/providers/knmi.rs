@@ -0,0 +1,336 @@
+use crate::providers::http_request::{request_cached, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::station_lookup::{find_closest_station_with, Station};
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, Hectopascals, MetersPerSecond, Ratio,
+};
+use crate::providers::{
+    calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::{anyhow, Context};
+use log::trace;
+use netcdf_reader::NcFile;
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SOURCE_URI: &str = "nl.knmi";
+const DATASET_URL: &str = "https://api.dataplatform.knmi.nl/open-data/v1/datasets/10-minute-in-situ-meteorological-observations/versions/1.0/files";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Knmi {
+    api_key: String,
+    #[serde(default = "default_refresh_interval")]
+    #[serde(with = "humantime_serde")]
+    refresh_interval: Duration,
+}
+
+// New observation files are published every 10 minutes
+const fn default_refresh_interval() -> Duration {
+    Duration::from_secs(60 * 10)
+}
+
+#[derive(Deserialize, Debug)]
+struct KnmiFileList {
+    files: Vec<KnmiFile>,
+}
+
+#[derive(Deserialize, Debug)]
+struct KnmiFile {
+    filename: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct KnmiDownloadUrl {
+    temporary_download_url: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Observation {
+    station_id: String,
+    name: String,
+    latitude: Coordinate,
+    longitude: Coordinate,
+    temperature: Option<f64>,
+    relative_humidity: Option<f64>,
+    dew_point: Option<f64>,
+    pressure: Option<f64>,
+    wind_speed: Option<f64>,
+    wind_gust: Option<f64>,
+    wind_direction: Option<f64>,
+}
+
+impl Station for Observation {
+    fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude.clone(),
+            longitude: self.longitude.clone(),
+        }
+    }
+}
+
+/// Latest non-missing value per station of a variable with the dimensions (station, time)
+fn latest_values_per_station(
+    netcdf: &NcFile,
+    name: &str,
+    station_count: usize,
+) -> anyhow::Result<Vec<Option<f64>>> {
+    let variable = netcdf
+        .variable(name)
+        .with_context(|| format!("Missing variable {name}"))?;
+    let dimensions: Vec<&str> = variable
+        .dimensions()
+        .iter()
+        .map(|dimension| dimension.name.as_str())
+        .collect();
+    // Fill values are masked as NaN
+    let values: Vec<Option<f64>> = netcdf
+        .read_variable_masked(name)
+        .with_context(|| format!("Failed to read variable {name}"))?
+        .iter()
+        .map(|value| (!value.is_nan()).then_some(*value))
+        .collect();
+
+    match (dimensions.as_slice(), variable.shape().as_slice()) {
+        (["station"], [_]) => Ok(values),
+        (["station", "time"], [_, time_count]) => Ok(values
+            .chunks(usize::try_from(*time_count)?.max(1))
+            .map(|per_time| per_time.iter().rev().find_map(|value| *value))
+            .collect()),
+        (["time", "station"], [_, _]) => Ok((0..station_count)
+            .map(|station| {
+                values
+                    .iter()
+                    .skip(station)
+                    .step_by(station_count.max(1))
+                    .rev()
+                    .find_map(|value| *value)
+            })
+            .collect()),
+        (unexpected, _) => Err(anyhow!(
+            "Unexpected dimensions {unexpected:?} for variable {name}"
+        )),
+    }
+}
+
+fn parse_observations(body: &[u8]) -> anyhow::Result<Vec<Observation>> {
+    let netcdf = NcFile::from_bytes(body).context("Failed to parse KNMI observation file")?;
+
+    let station_ids = netcdf.read_variable_as_strings("station")?;
+    let names = netcdf.read_variable_as_strings("stationname")?;
+    let station_count = station_ids.len();
+    let values = |name| latest_values_per_station(&netcdf, name, station_count);
+
+    let latitudes = values("lat")?;
+    let longitudes = values("lon")?;
+    let temperatures = values("ta")?;
+    let relative_humidities = values("rh")?;
+    let dew_points = values("td")?;
+    let pressures = values("pp")?;
+    let wind_speeds = values("ff")?;
+    let wind_gusts = values("gff")?;
+    let wind_directions = values("dd")?;
+
+    let value_at =
+        |per_station: &[Option<f64>], index: usize| per_station.get(index).copied().flatten();
+
+    station_ids
+        .into_iter()
+        .enumerate()
+        .map(|(index, station_id)| {
+            Ok(Observation {
+                name: names.get(index).cloned().unwrap_or_default(),
+                latitude: value_at(&latitudes, index)
+                    .ok_or_else(|| anyhow!("Missing latitude for station {station_id}"))?
+                    .into(),
+                longitude: value_at(&longitudes, index)
+                    .ok_or_else(|| anyhow!("Missing longitude for station {station_id}"))?
+                    .into(),
+                temperature: value_at(&temperatures, index),
+                relative_humidity: value_at(&relative_humidities, index),
+                dew_point: value_at(&dew_points, index),
+                pressure: value_at(&pressures, index),
+                wind_speed: value_at(&wind_speeds, index),
+                wind_gust: value_at(&wind_gusts, index),
+                wind_direction: value_at(&wind_directions, index),
+                station_id,
+            })
+        })
+        .collect()
+}
+
+impl RegisteredProvider for Knmi {
+    const KEY: &'static str = "knmi";
+}
+
+impl WeatherProvider for Knmi {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let file_list_url = Url::parse_with_params(
+            DATASET_URL,
+            &[
+                ("maxKeys", "1"),
+                ("sorting", "desc"),
+                ("orderBy", "created"),
+            ],
+        )?;
+
+        let file_list: KnmiFileList = request_cached(
+            &HttpCacheRequest::new_json_request(
+                SOURCE_URI,
+                client,
+                cache,
+                &Method::GET,
+                &file_list_url,
+            )
+            .with_header(AUTHORIZATION, HeaderValue::from_str(&self.api_key)?),
+        )?;
+
+        let latest_file = file_list
+            .files
+            .first()
+            .ok_or_else(|| anyhow!("No observation files available"))?;
+        trace!("Using latest observation file {}", latest_file.filename);
+
+        let download_url_url = Url::parse(&format!("{DATASET_URL}/{}/url", latest_file.filename))?;
+
+        let download_url: KnmiDownloadUrl = request_cached(
+            &HttpCacheRequest::new_json_request(
+                SOURCE_URI,
+                client,
+                cache,
+                &Method::GET,
+                &download_url_url,
+            )
+            .with_header(AUTHORIZATION, HeaderValue::from_str(&self.api_key)?),
+        )?;
+
+        // The download URL is pre-signed and must be requested without authorization header
+        let observations = request_cached(&HttpCacheRequest::new(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &Url::parse(&download_url.temporary_download_url)?,
+            |body| parse_observations(body),
+        ))?;
+
+        let closest_station =
+            find_closest_station_with(&request.query, &observations, |observation| {
+                observation.temperature.is_some()
+            })?;
+        trace!("Found closest weather station {:?}", closest_station);
+
+        let coordinates = closest_station.coordinates();
+        let distance = calculate_distance(&request.query, &coordinates);
+
+        Ok(Weather {
+            source: SOURCE_URI.into(),
+            location: request.name.clone(),
+            city: Some(closest_station.name.clone()),
+            coordinates,
+            distance: Some(distance),
+            temperature: closest_station
+                .temperature
+                .map(Celsius::from_f64)
+                .ok_or_else(|| anyhow!("No temperature for {}", closest_station.name))?,
+            relative_humidity: closest_station.relative_humidity.map(Ratio::Percentage),
+            dew_point: closest_station.dew_point.map(Celsius::from_f64),
+            pressure: closest_station.pressure.map(Hectopascals::from),
+            wind_speed: closest_station.wind_speed.map(MetersPerSecond::from),
+            wind_gust: closest_station.wind_gust.map(MetersPerSecond::from),
+            wind_direction: closest_station.wind_direction.map(Degrees::from),
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.refresh_interval
+    }
+
+    fn cache_cardinality(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse_observations {
+        use crate::providers::knmi::{parse_observations, Observation};
+        use pretty_assertions::assert_eq;
+
+        // Synthetic NetCDF-4 file written for these tests. It has the dimensions, variables,
+        // attributes, fill values and deflate compression of the KNMI 10 minute in-situ
+        // observations, but made up values for three stations, the last of which does not measure
+        // temperature
+        const SAMPLE: &[u8] = include_bytes!("fixtures/knmi_10m_obs_sample.nc");
+
+        fn sample_observations() -> Vec<Observation> {
+            vec![
+                Observation {
+                    station_id: "06260".into(),
+                    name: "DE BILT AWS".into(),
+                    latitude: 52.098_821_f64.into(),
+                    longitude: 5.179_698_f64.into(),
+                    temperature: Some(11.3_f64),
+                    relative_humidity: Some(87.0_f64),
+                    dew_point: Some(9.2_f64),
+                    pressure: Some(1013.4_f64),
+                    wind_speed: Some(3.6_f64),
+                    wind_gust: Some(6.1_f64),
+                    wind_direction: Some(230.0_f64),
+                },
+                Observation {
+                    station_id: "06240".into(),
+                    name: "AMSTERDAM/SCHIPHOL AP".into(),
+                    latitude: 52.317_501_f64.into(),
+                    longitude: 4.790_292_f64.into(),
+                    temperature: Some(12.1_f64),
+                    relative_humidity: Some(82.0_f64),
+                    dew_point: Some(9.1_f64),
+                    pressure: Some(1012.9_f64),
+                    wind_speed: Some(6.2_f64),
+                    wind_gust: Some(9.8_f64),
+                    wind_direction: Some(240.0_f64),
+                },
+                Observation {
+                    station_id: "06201".into(),
+                    name: "D15-FA-1".into(),
+                    latitude: 54.325_666_f64.into(),
+                    longitude: 2.935_833_f64.into(),
+                    temperature: None,
+                    relative_humidity: None,
+                    dew_point: None,
+                    pressure: Some(1010.2_f64),
+                    wind_speed: Some(11.4_f64),
+                    wind_gust: Some(14.0_f64),
+                    wind_direction: Some(250.0_f64),
+                },
+            ]
+        }
+
+        #[test]
+        fn parse_sample_file() {
+            assert_eq!(
+                parse_observations(SAMPLE).expect("Parsing works"),
+                sample_observations()
+            );
+        }
+
+        #[test]
+        fn reject_truncated_file() {
+            parse_observations(b"\x89HDF\r\n\x1a\n").expect_err("Truncated file is rejected");
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinates, Degrees, MetersPerSecond, Pascals, Ratio, ToHectopascals,
+};
+use crate::providers::{
+    calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+
+const SOURCE_URI: &str = "uk.gov.metoffice";
+const ENDPOINT_URL: &str = "https://data.hub.api.metoffice.gov.uk/sitespecific/v0/point/hourly";
+const API_KEY_HEADER: &str = "apikey";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MetOffice {
+    api_key: String,
+    #[serde(flatten)]
+    cache: Configuration,
+}
+
+#[derive(Deserialize, Debug)]
+struct MetOfficeResponse {
+    features: Vec<MetOfficeFeature>,
+}
+
+#[derive(Deserialize, Debug)]
+struct MetOfficeFeature {
+    geometry: MetOfficeGeometry,
+    properties: MetOfficeProperties,
+}
+
+// GeoJSON coordinates are ordered longitude, latitude, elevation
+#[derive(Deserialize, Debug)]
+struct MetOfficeGeometry {
+    coordinates: (f64, f64, Option<f64>),
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct MetOfficeProperties {
+    location: Option<MetOfficeLocation>,
+    time_series: Vec<MetOfficeTimeStep>,
+}
+
+#[derive(Deserialize, Debug)]
+struct MetOfficeLocation {
+    name: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct MetOfficeTimeStep {
+    #[serde(with = "minute_precision_date_format")]
+    time: DateTime<Utc>,
+    screen_temperature: Celsius,
+    screen_relative_humidity: Option<f64>,
+    screen_dew_point_temperature: Option<Celsius>,
+    mslp: Option<Pascals>,
+    #[serde(rename = "windSpeed10m")]
+    wind_speed: Option<MetersPerSecond>,
+    #[serde(rename = "windGustSpeed10m")]
+    wind_gust: Option<MetersPerSecond>,
+    #[serde(rename = "windDirectionFrom10m")]
+    wind_direction: Option<Degrees>,
+}
+
+mod minute_precision_date_format {
+    use chrono::{DateTime, NaiveDateTime, Utc};
+    use serde::de::Error;
+    use serde::{self, Deserialize, Deserializer};
+
+    const FORMAT: &str = "%Y-%m-%dT%H:%MZ";
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        NaiveDateTime::parse_from_str(&s, FORMAT)
+            .map(|v| v.and_utc())
+            .map_err(Error::custom)
+    }
+}
+
+/// The hourly spot data is a forecast time series, so pick the latest step that is not in the future
+fn current_time_step(
+    time_series: &[MetOfficeTimeStep],
+    now: DateTime<Utc>,
+) -> Option<&MetOfficeTimeStep> {
+    time_series
+        .iter()
+        .filter(|step| step.time <= now)
+        .max_by_key(|step| step.time)
+        .or_else(|| time_series.first())
+}
+
+impl RegisteredProvider for MetOffice {
+    const KEY: &'static str = "met_office";
+}
+
+impl WeatherProvider for MetOffice {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let url = Url::parse_with_params(
+            ENDPOINT_URL,
+            &[
+                ("latitude", request.query.latitude.to_string()),
+                ("longitude", request.query.longitude.to_string()),
+                ("includeLocationName", "true".into()),
+                ("excludeParameterMetadata", "true".into()),
+            ],
+        )?;
+
+        let response: MetOfficeResponse = request_cached(
+            &HttpCacheRequest::new_json_request(SOURCE_URI, client, cache, &Method::GET, &url)
+                .with_header(
+                    HeaderName::from_static(API_KEY_HEADER),
+                    HeaderValue::from_str(&self.api_key)?,
+                ),
+        )?;
+
+        let feature = response
+            .features
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Empty feature collection"))?;
+
+        let step = current_time_step(&feature.properties.time_series, SystemTime::now().into())
+            .ok_or_else(|| anyhow!("Empty time series"))?;
+
+        let (longitude, latitude, _) = feature.geometry.coordinates;
+        let coordinates = Coordinates {
+            latitude: latitude.into(),
+            longitude: longitude.into(),
+        };
+        let distance = calculate_distance(&request.query, &coordinates);
+
+        Ok(Weather {
+            location: request.name.clone(),
+            source: SOURCE_URI.into(),
+            city: feature.properties.location.map(|location| location.name),
+            coordinates,
+            distance: Some(distance),
+            temperature: step.screen_temperature,
+            relative_humidity: step.screen_relative_humidity.map(Ratio::Percentage),
+            dew_point: step.screen_dew_point_temperature,
+            pressure: step.mslp.map(|v| v.to_hectopascals()),
+            wind_speed: step.wind_speed,
+            wind_gust: step.wind_gust,
+            wind_direction: step.wind_direction,
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse_response {
+        use crate::providers::met_office::{current_time_step, MetOfficeResponse};
+        use crate::providers::units::{Celsius, Pascals};
+        use chrono::DateTime;
+        use pretty_assertions::assert_eq;
+
+        const RESPONSE: &str = r#"{
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [-0.1248, 51.5081, 11.0]},
+                "properties": {
+                    "location": {"name": "London"},
+                    "requestPointDistance": 221.7807,
+                    "modelRunDate": "2026-10-18T10:00Z",
+                    "timeSeries": [
+                        {"time": "2026-10-18T10:00Z", "screenTemperature": 12.67, "screenDewPointTemperature": 9.71, "windSpeed10m": 3.09, "windDirectionFrom10m": 226, "windGustSpeed10m": 7.72, "screenRelativeHumidity": 82.17, "mslp": 101210, "uvIndex": 1},
+                        {"time": "2026-10-18T11:00Z", "screenTemperature": 13.52, "screenDewPointTemperature": 9.64, "windSpeed10m": 3.44, "windDirectionFrom10m": 231, "windGustSpeed10m": 8.23, "screenRelativeHumidity": 77.31, "mslp": 101190, "uvIndex": 2}
+                    ]
+                }
+            }]
+        }"#;
+
+        #[test]
+        fn pick_current_time_step() {
+            let response: MetOfficeResponse =
+                serde_json::from_str(RESPONSE).expect("Parsing works");
+            let feature = response.features.first().expect("One feature");
+
+            assert_eq!(
+                feature.geometry.coordinates,
+                (-0.1248_f64, 51.5081_f64, Some(11.0_f64))
+            );
+            assert_eq!(
+                feature
+                    .properties
+                    .location
+                    .as_ref()
+                    .map(|location| location.name.as_str()),
+                Some("London")
+            );
+
+            let step = current_time_step(
+                &feature.properties.time_series,
+                DateTime::parse_from_rfc3339("2026-10-18T10:42:00Z")
+                    .expect("Valid date")
+                    .into(),
+            )
+            .expect("Current time step");
+
+            assert_eq!(step.screen_temperature, Celsius::from(12.67));
+            assert_eq!(step.screen_relative_humidity, Some(82.17_f64));
+            assert_eq!(step.mslp, Some(Pascals::from(101_210_f64)));
+        }
+
+        #[test]
+        fn fall_back_to_first_time_step() {
+            let response: MetOfficeResponse =
+                serde_json::from_str(RESPONSE).expect("Parsing works");
+            let feature = response.features.first().expect("One feature");
+
+            let step = current_time_step(
+                &feature.properties.time_series,
+                DateTime::parse_from_rfc3339("2026-10-18T09:00:00Z")
+                    .expect("Valid date")
+                    .into(),
+            )
+            .expect("Current time step");
+
+            assert_eq!(step.screen_temperature, Celsius::from(12.67));
+        }
+    }
+}
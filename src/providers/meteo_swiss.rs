@@ -0,0 +1,254 @@
+use crate::providers::http_request::{
+    decode_latin1_body, request_cached, Configuration, HttpCacheRequest,
+};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::station_lookup::{find_closest_station_with, Station};
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, Hectopascals, MetersPerSecond, Ratio,
+};
+use crate::providers::{
+    calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::{anyhow, Context};
+use csv::Trim;
+use log::trace;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SOURCE_URI: &str = "ch.meteoswiss";
+const STATION_LIST_URL: &str = "https://data.geo.admin.ch/ch.meteoschweiz.messnetz-automatisch/ch.meteoschweiz.messnetz-automatisch_en.csv";
+const MEASUREMENTS_URL: &str =
+    "https://data.geo.admin.ch/ch.meteoschweiz.messwerte-aktuell/VQHA80.csv";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MeteoSwiss {
+    #[serde(flatten)]
+    cache: Configuration,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct WeatherStation {
+    #[serde(rename = "Abbr.")]
+    station_id: String,
+    #[serde(rename = "Station")]
+    name: String,
+    #[serde(rename = "Latitude")]
+    latitude: Coordinate,
+    #[serde(rename = "Longitude")]
+    longitude: Coordinate,
+}
+
+impl Station for WeatherStation {
+    fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude.clone(),
+            longitude: self.longitude.clone(),
+        }
+    }
+}
+
+fn parse_weather_station_list_csv(data: &str) -> anyhow::Result<Vec<WeatherStation>> {
+    let reader = csv::ReaderBuilder::new()
+        .delimiter(b';')
+        .trim(Trim::All)
+        .flexible(true)
+        .from_reader(data.as_bytes());
+
+    reader
+        .into_deserialize::<WeatherStation>()
+        .collect::<Result<_, _>>()
+        .context("Failed to parse weather station list CSV file")
+}
+
+// Missing values are reported as "-", which is why every measurement is optional
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct Measurement {
+    #[serde(rename = "Station/Location")]
+    station_id: String,
+    #[serde(rename = "Date")]
+    _time: String,
+    #[serde(rename = "tre200s0", deserialize_with = "csv::invalid_option")]
+    temperature: Option<Celsius>,
+    #[serde(rename = "ure200s0", deserialize_with = "csv::invalid_option")]
+    relative_humidity: Option<f64>,
+    #[serde(rename = "tde200s0", deserialize_with = "csv::invalid_option")]
+    dew_point: Option<Celsius>,
+    #[serde(rename = "pp0qffs0", deserialize_with = "csv::invalid_option")]
+    pressure: Option<Hectopascals>,
+    #[serde(rename = "fkl010z0", deserialize_with = "csv::invalid_option")]
+    wind_speed: Option<MetersPerSecond>,
+    #[serde(rename = "fkl010z1", deserialize_with = "csv::invalid_option")]
+    wind_gust: Option<MetersPerSecond>,
+    #[serde(rename = "dkl010z0", deserialize_with = "csv::invalid_option")]
+    wind_direction: Option<Degrees>,
+    #[serde(rename = "sre000z0", deserialize_with = "csv::invalid_option")]
+    sunshine_minutes: Option<f64>,
+}
+
+fn parse_measurement_data_csv(data: &str) -> anyhow::Result<Vec<Measurement>> {
+    let reader = csv::ReaderBuilder::new()
+        .delimiter(b';')
+        .trim(Trim::All)
+        .from_reader(data.as_bytes());
+
+    reader
+        .into_deserialize::<Measurement>()
+        .collect::<Result<_, _>>()
+        .context("Failed to parse measurement data CSV file")
+}
+
+impl RegisteredProvider for MeteoSwiss {
+    const KEY: &'static str = "meteo_swiss";
+}
+
+impl WeatherProvider for MeteoSwiss {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let stations = request_cached(&HttpCacheRequest::new(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &Url::parse(STATION_LIST_URL)?,
+            |body| parse_weather_station_list_csv(&decode_latin1_body(body)),
+        ))?;
+
+        let measurements = request_cached(&HttpCacheRequest::new(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &Url::parse(MEASUREMENTS_URL)?,
+            |body| parse_measurement_data_csv(&decode_latin1_body(body)),
+        ))?;
+
+        let closest_station = find_closest_station_with(&request.query, &stations, |station| {
+            measurements.iter().any(|measurement| {
+                measurement.station_id == station.station_id && measurement.temperature.is_some()
+            })
+        })?;
+        trace!("Found closest weather station {:?}", closest_station);
+
+        let measurement = measurements
+            .iter()
+            .find(|measurement| measurement.station_id == closest_station.station_id)
+            .ok_or_else(|| anyhow!("No measurement for station {}", closest_station.name))?;
+
+        let coordinates = closest_station.coordinates();
+        let distance = calculate_distance(&request.query, &coordinates);
+
+        Ok(Weather {
+            source: SOURCE_URI.into(),
+            location: request.name.clone(),
+            city: Some(closest_station.name.clone()),
+            coordinates,
+            distance: Some(distance),
+            temperature: measurement
+                .temperature
+                .ok_or_else(|| anyhow!("No temperature for station {}", closest_station.name))?,
+            relative_humidity: measurement.relative_humidity.map(Ratio::Percentage),
+            dew_point: measurement.dew_point,
+            pressure: measurement.pressure,
+            wind_speed: measurement.wind_speed,
+            wind_gust: measurement.wind_gust,
+            wind_direction: measurement.wind_direction,
+            sunshine_duration: measurement
+                .sunshine_minutes
+                .and_then(|minutes| Duration::try_from_secs_f64(minutes * 60.0).ok()),
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+
+    fn cache_cardinality(&self) -> usize {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse_weather_station_list {
+        use crate::providers::meteo_swiss::{parse_weather_station_list_csv, WeatherStation};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_short_list() {
+            assert_eq!(
+                parse_weather_station_list_csv("Station;Abbr.;WIGOS-ID;Station type;Data Owner;Data since;Station height m. a. sea level;Barometric altitude m. a. ground;CoordinatesE;CoordinatesN;Latitude;Longitude;Exposition;Canton;Measurements;Link
+Aadorf / T\u{e4}nikon;TAE;0-20000-0-06679;Weather station;MeteoSwiss;01.01.1981;539;3;2710518;1259824;47.479892;8.904928;plain;TG;Wind, temperature, humidity, pressure;https://www.meteoswiss.admin.ch
+Basel / Binningen;BAS;0-20000-0-06601;Climate station;MeteoSwiss;01.01.1864;316;2;2610911;1265600;47.541142;7.583525;plain;BL;Wind, temperature;https://www.meteoswiss.admin.ch
+").expect("Parsing works"),
+                &[
+                    WeatherStation {
+                        station_id: "TAE".into(),
+                        name: "Aadorf / T\u{e4}nikon".into(),
+                        latitude: 47.479_892_f64.into(),
+                        longitude: 8.904_928_f64.into(),
+                    },
+                    WeatherStation {
+                        station_id: "BAS".into(),
+                        name: "Basel / Binningen".into(),
+                        latitude: 47.541_142_f64.into(),
+                        longitude: 7.583_525_f64.into(),
+                    }
+                ]
+            );
+        }
+    }
+
+    mod parse_measurement_data_csv {
+        use crate::providers::meteo_swiss::{parse_measurement_data_csv, Measurement};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_example() {
+            assert_eq!(
+                &*parse_measurement_data_csv(
+                    "Station/Location;Date;tre200s0;rre150z0;sre000z0;gre000z0;ure200s0;tde200s0;dkl010z0;fu3010z0;fu3gen;fkl010z0;fkl010z1;prestas0;pp0qffs0;pp0qnhs0\n\
+                    TAE;202610181000;11.2;0.0;4.0;210;78.3;7.5;245;11.2;23.4;3.1;6.5;955.9;1018.4;1018.1\n\
+                    ARO;202610181000;-;-;-;-;-;-;-;-;-;-;-;-;-;-"
+                )
+                .expect("Parsing works"),
+                [
+                    Measurement {
+                        station_id: "TAE".into(),
+                        _time: "202610181000".into(),
+                        temperature: Some(11.2.into()),
+                        relative_humidity: Some(78.3_f64),
+                        dew_point: Some(7.5.into()),
+                        pressure: Some(1018.4_f64.into()),
+                        wind_speed: Some(3.1_f64.into()),
+                        wind_gust: Some(6.5_f64.into()),
+                        wind_direction: Some(245_f64.into()),
+                        sunshine_minutes: Some(4_f64),
+                    },
+                    Measurement {
+                        station_id: "ARO".into(),
+                        _time: "202610181000".into(),
+                        temperature: None,
+                        relative_humidity: None,
+                        dew_point: None,
+                        pressure: None,
+                        wind_speed: None,
+                        wind_gust: None,
+                        wind_direction: None,
+                        sunshine_minutes: None,
+                    }
+                ]
+            );
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
-use crate::providers::units::{Celsius, Coordinates};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinates, Degrees, Hectopascals, Meters, MetersPerSecond, Millimeters, Ratio,
+};
 use crate::providers::{
     calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
 };
@@ -8,36 +11,86 @@ use reqwest::blocking::Client;
 use reqwest::{Method, Url};
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
-use std::time::Duration;
+use std::iter;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 type HmacSha256 = Hmac<Sha256>;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Meteoblue {
     api_key: String,
+    /// Packages requested in addition to the current package, e.g. `basic-1h` or `agro-1h`. Relative
+    /// humidity, pressure, wind direction and precipitation are only available with `basic-1h`
+    #[serde(default)]
+    packages: Vec<String>,
     #[serde(flatten)]
     cache: Configuration,
 }
 
 const SOURCE_URI: &str = "com.meteoblue";
-const ENDPOINT_URL: &str = "https://my.meteoblue.com/packages/current";
+const ENDPOINT_URL: &str = "https://my.meteoblue.com/packages";
+const CURRENT_PACKAGE: &str = "current";
 
 #[derive(Deserialize, Debug)]
 struct MeteoblueResponseMetadata {
     name: String,
     #[serde(flatten)]
     coordinates: Coordinates,
+    height: Option<f64>,
 }
 
 #[derive(Deserialize, Debug)]
 struct MeteoblueResponseDataCurrent {
     temperature: Celsius,
+    windspeed: Option<MetersPerSecond>,
+}
+
+// Hourly packages share the time series, each package adds its own variables. Times are requested
+// as UTC timestamps
+#[derive(Deserialize, Debug, Default)]
+struct MeteoblueResponseData1h {
+    time: Vec<i64>,
+    #[serde(default)]
+    relativehumidity: Vec<Option<f64>>,
+    #[serde(default)]
+    sealevelpressure: Vec<Option<Hectopascals>>,
+    #[serde(default)]
+    windspeed: Vec<Option<MetersPerSecond>>,
+    #[serde(default)]
+    winddirection: Vec<Option<Degrees>>,
+    #[serde(default)]
+    precipitation: Vec<Option<f64>>,
 }
 
 #[derive(Deserialize, Debug)]
 struct MeteoblueResponse {
     metadata: MeteoblueResponseMetadata,
     data_current: MeteoblueResponseDataCurrent,
+    data_1h: Option<MeteoblueResponseData1h>,
+}
+
+fn packages_url(packages: &[String]) -> anyhow::Result<Url> {
+    let path = iter::once(CURRENT_PACKAGE)
+        .chain(packages.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("_");
+
+    Ok(Url::parse(&format!("{ENDPOINT_URL}/{path}"))?)
+}
+
+fn value_at<T: Copy>(values: &[Option<T>], index: Option<usize>) -> Option<T> {
+    index.and_then(|i| values.get(i).copied().flatten())
+}
+
+impl MeteoblueResponseData1h {
+    /// Index of the latest hour that is not in the future
+    fn current_index(&self, now: i64) -> Option<usize> {
+        self.time.iter().rposition(|&time| time <= now)
+    }
+}
+
+impl RegisteredProvider for Meteoblue {
+    const KEY: &'static str = "meteoblue";
 }
 
 impl WeatherProvider for Meteoblue {
@@ -52,11 +105,12 @@ impl WeatherProvider for Meteoblue {
         request: &WeatherRequest<Coordinates>,
     ) -> anyhow::Result<Weather> {
         let url = Url::parse_with_params(
-            ENDPOINT_URL,
+            packages_url(&self.packages)?.as_str(),
             &[
                 ("lat", request.query.latitude.to_string()),
                 ("lon", request.query.longitude.to_string()),
                 ("format", "json".into()),
+                ("timeformat", "timestamp_utc".into()),
                 ("apikey", self.api_key.clone()),
             ],
         )?;
@@ -74,6 +128,11 @@ impl WeatherProvider for Meteoblue {
         ))?;
 
         let distance = calculate_distance(&request.query, &response.metadata.coordinates);
+        let hourly = response.data_1h.unwrap_or_default();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX));
+        let hour = hourly.current_index(now);
 
         Ok(Weather {
             source: SOURCE_URI.into(),
@@ -81,8 +140,17 @@ impl WeatherProvider for Meteoblue {
             city: Some(response.metadata.name).filter(|v| !v.is_empty()),
             coordinates: response.metadata.coordinates,
             distance: Some(distance),
+            elevation: response.metadata.height.map(Meters::from),
             temperature: response.data_current.temperature,
-            relative_humidity: None,
+            relative_humidity: value_at(&hourly.relativehumidity, hour).map(Ratio::Percentage),
+            pressure: value_at(&hourly.sealevelpressure, hour),
+            wind_speed: response
+                .data_current
+                .windspeed
+                .or_else(|| value_at(&hourly.windspeed, hour)),
+            wind_direction: value_at(&hourly.winddirection, hour),
+            precipitation: value_at(&hourly.precipitation, hour).map(Millimeters::from),
+            ..Weather::default()
         })
     }
 
@@ -105,3 +173,79 @@ fn create_signature(api_key: &str, url: &Url) -> anyhow::Result<String> {
 
     Ok(hex::encode(key.into_bytes()))
 }
+
+#[cfg(test)]
+mod tests {
+    mod packages_url {
+        use crate::providers::meteoblue::{packages_url, Meteoblue};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn current_only() {
+            assert_eq!(
+                packages_url(&[]).expect("Valid URL").as_str(),
+                "https://my.meteoblue.com/packages/current"
+            );
+        }
+
+        #[test]
+        fn additional_packages() {
+            assert_eq!(
+                packages_url(&["basic-1h".into(), "agro-1h".into()])
+                    .expect("Valid URL")
+                    .as_str(),
+                "https://my.meteoblue.com/packages/current_basic-1h_agro-1h"
+            );
+        }
+
+        #[test]
+        fn only_current_package_by_default() {
+            let provider: Meteoblue =
+                toml::from_str(r#"api_key = "secret""#).expect("Valid configuration");
+
+            assert_eq!(
+                packages_url(&provider.packages)
+                    .expect("Valid URL")
+                    .as_str(),
+                "https://my.meteoblue.com/packages/current"
+            );
+        }
+    }
+
+    mod parse_response {
+        use crate::providers::meteoblue::{value_at, MeteoblueResponse};
+        use crate::providers::units::Celsius;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_hourly_package() {
+            let response: MeteoblueResponse = serde_json::from_str(
+                r#"{
+                    "metadata": {"name": "Basel", "latitude": 47.56, "longitude": 7.57, "height": 279, "timezone_abbrevation": "CEST", "utc_timeoffset": 2.0, "modelrun_utc": "2026-10-18 00:00", "modelrun_updatetime_utc": "2026-10-18 04:41"},
+                    "units": {"time": "YYYY-MM-DD hh:mm", "temperature": "C", "windspeed": "ms-1", "precipitation": "mm", "relativehumidity": "percent"},
+                    "data_current": {"time": 1760781600, "isobserveddata": 0, "metarid": null, "isdaylight": 1, "windspeed": 2.1, "zenithangle": 58.1, "pictocode_detailed": 19, "pictocode": 3, "temperature": 11.4},
+                    "data_1h": {
+                        "time": [1760778000, 1760781600, 1760785200],
+                        "precipitation": [0.0, 0.2, 0.5],
+                        "temperature": [10.9, 11.4, 12.0],
+                        "windspeed": [1.9, 2.1, 2.6],
+                        "winddirection": [230, 240, 250],
+                        "relativehumidity": [84, 81, null],
+                        "sealevelpressure": [1016, 1016, 1015]
+                    }
+                }"#,
+            )
+            .expect("Parsing works");
+
+            assert_eq!(response.data_current.temperature, Celsius::from(11.4));
+            assert_eq!(response.metadata.height, Some(279_f64));
+
+            let hourly = response.data_1h.expect("Hourly data");
+            let index = hourly.current_index(1_760_782_000);
+            assert_eq!(index, Some(1));
+            assert_eq!(value_at(&hourly.relativehumidity, index), Some(81_f64));
+            assert_eq!(value_at(&hourly.precipitation, index), Some(0.2_f64));
+            assert_eq!(value_at(&hourly.relativehumidity, Some(2)), None);
+        }
+    }
+}
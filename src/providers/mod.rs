@@ -1,87 +1,128 @@
+mod accuweather;
+mod bright_sky;
+mod command;
 mod deutscher_wetterdienst;
+mod dwd_mosmix;
+mod eccc;
+mod fields;
+mod generic_json;
+mod geosphere_austria;
+mod home_assistant;
 mod http_request;
+mod instances;
+mod knmi;
+mod met_office;
+mod meteo_swiss;
 mod meteoblue;
+mod mqtt;
+mod netatmo;
 mod nogoodnik;
 mod open_meteo;
 mod open_weather;
+mod pirate_weather;
+mod registry;
+mod smhi;
+mod station_lookup;
 mod tomorrow;
 pub mod units;
+mod wunderground;
 
-use crate::providers::deutscher_wetterdienst::DeutscherWetterdienst;
-use crate::providers::meteoblue::Meteoblue;
-use crate::providers::nogoodnik::Nogoodnik;
-use crate::providers::open_meteo::OpenMeteo;
-use crate::providers::open_weather::OpenWeather;
-use crate::providers::tomorrow::Tomorrow;
-use crate::providers::units::{Celsius, Meters, Ratio};
+use crate::providers::registry::ConfiguredProviders;
+use crate::providers::units::{
+    Celsius, Degrees, Hectopascals, Meters, MetersPerSecond, Millimeters, MillimetersPerHour, Ratio,
+};
 use geo::{HaversineDistance, Point};
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::Debug;
-use std::sync::Arc;
 use std::time::Duration;
-use std::vec::IntoIter;
+use toml::Value;
 use units::Coordinates;
 
+/// Configuration sections of the provider types, keyed by their registered config key
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Providers {
-    open_weather: Option<OpenWeather>,
-    meteoblue: Option<Meteoblue>,
-    tomorrow: Option<Tomorrow>,
-    deutscher_wetterdienst: Option<DeutscherWetterdienst>,
-    open_meteo: Option<OpenMeteo>,
-    nogoodnik: Option<Nogoodnik>,
-}
-
-impl IntoIterator for Providers {
-    type Item = Arc<dyn WeatherProvider + Send + Sync>;
-    type IntoIter = IntoIter<Arc<dyn WeatherProvider + Send + Sync>>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        let mut vec: Vec<Arc<dyn WeatherProvider + Send + Sync>> = vec![];
-
-        if let Some(provider) = self.open_weather {
-            vec.push(Arc::new(provider));
-        }
-
-        if let Some(provider) = self.meteoblue {
-            vec.push(Arc::new(provider));
-        }
-
-        if let Some(provider) = self.tomorrow {
-            vec.push(Arc::new(provider));
-        }
+#[serde(transparent)]
+pub struct Providers(BTreeMap<String, Value>);
 
-        if let Some(provider) = self.deutscher_wetterdienst {
-            vec.push(Arc::new(provider));
-        }
-
-        if let Some(provider) = self.open_meteo {
-            vec.push(Arc::new(provider));
-        }
-
-        if let Some(provider) = self.nogoodnik {
-            vec.push(Arc::new(provider));
-        }
-
-        IntoIter::into_iter(vec.into_iter())
+impl Providers {
+    pub fn create(self) -> anyhow::Result<ConfiguredProviders> {
+        registry::create_providers(&registry::REGISTRY, self.0)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Weather {
     pub location: String,
     pub source: String,
+    pub instance: Option<String>,
+    pub model: Option<String>,
+    pub module: Option<String>,
     pub city: Option<String>,
     pub coordinates: Coordinates,
     pub distance: Option<Meters>,
+    pub elevation: Option<Meters>,
     pub temperature: Celsius,
     pub relative_humidity: Option<Ratio>,
+    pub dew_point: Option<Celsius>,
+    pub pressure: Option<Hectopascals>,
+    pub wind_speed: Option<MetersPerSecond>,
+    pub wind_gust: Option<MetersPerSecond>,
+    pub wind_direction: Option<Degrees>,
+    pub cloud_cover: Option<Ratio>,
+    pub sunshine_duration: Option<Duration>,
+    pub precipitation: Option<Millimeters>,
+    pub precipitation_intensity: Option<MillimetersPerHour>,
+    pub visibility: Option<Meters>,
+    pub uv_index: Option<f64>,
+    pub condition: Option<String>,
+    pub forecast: Vec<Forecast>,
+    /// Series without a measured temperature, e.g. of providers that only forecast or of stations
+    /// that only measure wind, do not export the temperature gauge
+    pub without_temperature: bool,
+    pub alerts: Vec<Alert>,
+    pub measurements: Vec<Measurement>,
+    /// Further modules of the same station, each exported as its own series with a module label
+    pub modules: Vec<Self>,
+}
+
+/// A measurement without a counterpart in the weather model, exported under its own name
+#[derive(Debug, Default, PartialEq)]
+pub struct Measurement {
+    pub name: String,
+    pub unit: Option<String>,
+    pub value: f64,
+}
+
+/// A weather warning that is currently in effect for the location
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Alert {
+    pub sender: String,
+    pub event: String,
+}
+
+/// Forecast values for a single step in the future, `hours_ahead` hours after the current weather
+#[derive(Debug, Default, PartialEq)]
+pub struct Forecast {
+    pub hours_ahead: u32,
+    pub temperature: Option<Celsius>,
+    pub dew_point: Option<Celsius>,
+    pub pressure: Option<Hectopascals>,
+    pub wind_speed: Option<MetersPerSecond>,
+    pub wind_gust: Option<MetersPerSecond>,
+    pub wind_direction: Option<Degrees>,
+    pub cloud_cover: Option<Ratio>,
+    pub precipitation: Option<Millimeters>,
 }
 
 pub trait WeatherProvider: Debug {
     fn id(&self) -> &str;
 
+    /// Name of the instance if several instances of the same provider type are configured
+    fn instance(&self) -> Option<&str> {
+        None
+    }
+
     fn for_coordinates(
         &self,
         client: &Client,
@@ -93,6 +134,24 @@ pub trait WeatherProvider: Debug {
     fn cache_cardinality(&self) -> usize {
         1
     }
+
+    /// Whether the provider has weather for a location, providers of local sensors only serve the
+    /// locations their sensors are configured for
+    fn serves(&self, _location: &str) -> bool {
+        true
+    }
+
+    /// Check the provider configuration against the number of configured locations, e.g. to
+    /// enforce request quotas
+    fn validate(&self, _location_count: usize) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Start background work of a valid provider on startup, e.g. subscribing to the values pushed
+    /// by local sensors, so they are available by the first request
+    fn start(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,515 @@
+use crate::config::NAME;
+use crate::providers::fields::{field_weather, pointer_value, Field};
+use crate::providers::http_request::Configuration;
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::Coordinates;
+use crate::providers::{HttpRequestCache, Weather, WeatherProvider, WeatherRequest};
+use anyhow::{anyhow, Context};
+use log::{debug, error, trace};
+use reqwest::blocking::Client;
+use rumqttc::{
+    matches, Connection, Event, MqttOptions, Packet, Publish, QoS, TlsConfiguration, Transport,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SOURCE_URI: &str = "local.mqtt";
+
+// Waiting between connection attempts keeps an unreachable broker from being hammered
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+// Brokers disconnect clients reusing a client ID, so every connection of a process gets its own
+static CONNECTION_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+type Readings = BTreeMap<String, BTreeMap<Field, Reading>>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Mqtt {
+    host: String,
+    #[serde(default = "default_port")]
+    port: u16,
+    client_id: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    #[serde(default)]
+    tls: bool,
+    ca_file: Option<PathBuf>,
+    #[serde(default = "default_stale_after")]
+    #[serde(with = "humantime_serde")]
+    stale_after: Duration,
+    topics: Vec<Subscription>,
+    #[serde(flatten)]
+    cache: Configuration,
+    #[serde(skip)]
+    readings: Arc<Mutex<Readings>>,
+}
+
+const fn default_port() -> u16 {
+    1883
+}
+
+/// Client ID unique to this process and connection, unless one is configured
+fn default_client_id() -> String {
+    format!(
+        "{NAME}-{}-{}",
+        process::id(),
+        CONNECTION_COUNT.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+const fn default_stale_after() -> Duration {
+    Duration::from_secs(10 * 60)
+}
+
+/// Topic to subscribe to, with JSON pointers to the values of its payloads
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Subscription {
+    topic: String,
+    location: String,
+    values: BTreeMap<Field, String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Reading {
+    value: f64,
+    received_at: Instant,
+}
+
+fn extract(payload: &[u8], pointer: &str) -> anyhow::Result<f64> {
+    pointer_value(&serde_json::from_slice(payload)?, pointer)
+}
+
+fn receive(topics: &[Subscription], state: &Mutex<Readings>, publish: &Publish) {
+    let received_at = Instant::now();
+    let mut readings = state.lock().unwrap_or_else(PoisonError::into_inner);
+
+    for topic in topics.iter().filter(|t| matches(&publish.topic, &t.topic)) {
+        let location = readings.entry(topic.location.clone()).or_default();
+
+        for (field, pointer) in &topic.values {
+            match extract(&publish.payload, pointer) {
+                Ok(value) => {
+                    trace!(
+                        "Received {field:?} {value} for {} from {}",
+                        topic.location,
+                        publish.topic
+                    );
+                    location.insert(*field, Reading { value, received_at });
+                }
+                Err(e) => debug!("Could not extract {field:?} from {}: {e}", publish.topic),
+            }
+        }
+    }
+
+    drop(readings);
+}
+
+fn run(
+    client: &rumqttc::Client,
+    mut connection: Connection,
+    topics: &[Subscription],
+    readings: &Mutex<Readings>,
+) {
+    for notification in connection.iter() {
+        match notification {
+            // Subscriptions are not kept by the broker with a clean session, so renew them on every connect
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                for topic in topics {
+                    if let Err(e) = client.try_subscribe(topic.topic.as_str(), QoS::AtMostOnce) {
+                        error!("Could not subscribe to MQTT topic {}: {e}", topic.topic);
+                    }
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => receive(topics, readings, &publish),
+            Ok(event) => trace!("MQTT event {event:?}"),
+            Err(e) => {
+                error!("MQTT connection error: {e}");
+                thread::sleep(RECONNECT_DELAY);
+            }
+        }
+    }
+}
+
+fn location_weather(
+    request: &WeatherRequest<Coordinates>,
+    readings: &BTreeMap<Field, Reading>,
+    stale_after: Duration,
+) -> anyhow::Result<Weather> {
+    let values = readings
+        .iter()
+        .filter(|(_, reading)| reading.received_at.elapsed() <= stale_after)
+        .map(|(field, reading)| (*field, reading.value))
+        .collect();
+
+    field_weather(SOURCE_URI, request, &values)
+}
+
+impl Mqtt {
+    fn options(&self) -> anyhow::Result<MqttOptions> {
+        let client_id = self.client_id.clone().unwrap_or_else(default_client_id);
+        let mut options = MqttOptions::new(client_id, &self.host, self.port);
+        options.set_keep_alive(KEEP_ALIVE);
+
+        if let Some(username) = &self.username {
+            options.set_credentials(username, self.password.clone().unwrap_or_default());
+        }
+
+        if self.tls {
+            let tls = match &self.ca_file {
+                Some(ca_file) => TlsConfiguration::SimpleNative {
+                    ca: fs::read(ca_file)
+                        .with_context(|| format!("Could not read CA file {}", ca_file.display()))?,
+                    client_auth: None,
+                },
+                None => TlsConfiguration::Native,
+            };
+            options.set_transport(Transport::tls_with_config(tls));
+        }
+
+        Ok(options)
+    }
+
+    fn subscribe(&self) -> anyhow::Result<()> {
+        let (client, connection) =
+            rumqttc::Client::new(self.options()?, self.topics.len().saturating_add(10));
+        let topics = self.topics.clone();
+        let readings = Arc::clone(&self.readings);
+
+        thread::Builder::new()
+            .name("mqtt".into())
+            .spawn(move || run(&client, connection, &topics, &readings))?;
+
+        Ok(())
+    }
+}
+
+impl RegisteredProvider for Mqtt {
+    const KEY: &'static str = "mqtt";
+}
+
+impl WeatherProvider for Mqtt {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        _client: &Client,
+        _cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let location = self
+            .readings
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&request.name)
+            .cloned()
+            .ok_or_else(|| anyhow!("No MQTT messages received for {}", request.name))?;
+
+        location_weather(request, &location, self.stale_after)
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+
+    fn serves(&self, location: &str) -> bool {
+        self.topics.iter().any(|topic| topic.location == location)
+    }
+
+    /// Subscribing on startup lets readings arrive before the first scrape
+    fn start(&self) -> anyhow::Result<()> {
+        self.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod default_client_id {
+        use crate::providers::mqtt::default_client_id;
+        use std::process;
+
+        #[test]
+        fn generate_unique_ids() {
+            let first = default_client_id();
+
+            assert!(
+                first.starts_with(&format!("prometheus-weathermen-{}-", process::id())),
+                "Client ID contains the process ID"
+            );
+            assert_ne!(first, default_client_id(), "Client IDs are unique");
+        }
+    }
+
+    mod extract {
+        use crate::providers::mqtt::extract;
+        use pretty_assertions::assert_eq;
+
+        const PAYLOAD: &[u8] =
+            br#"{"model": "Bresser-6in1", "temperature_C": 11.5, "humidity": "81", "battery_ok": true}"#;
+
+        #[test]
+        fn extract_number() {
+            assert_eq!(extract(PAYLOAD, "/temperature_C").ok(), Some(11.5_f64));
+        }
+
+        #[test]
+        fn extract_numeric_string() {
+            assert_eq!(extract(PAYLOAD, "/humidity").ok(), Some(81.0_f64));
+        }
+
+        #[test]
+        fn extract_plain_payload() {
+            assert_eq!(extract(b"1013.2", "").ok(), Some(1013.2_f64));
+        }
+
+        #[test]
+        fn fail_on_missing_value() {
+            let error = extract(PAYLOAD, "/pressure_hPa").expect_err("Extraction fails");
+
+            assert_eq!(
+                error.to_string(),
+                r#"Document has no value at "/pressure_hPa""#
+            );
+        }
+
+        #[test]
+        fn fail_on_non_numeric_value() {
+            let error = extract(PAYLOAD, "/battery_ok").expect_err("Extraction fails");
+
+            assert_eq!(
+                error.to_string(),
+                r#"Value at "/battery_ok" is not a number: true"#
+            );
+        }
+    }
+
+    mod location_weather {
+        use crate::providers::fields::Field;
+        use crate::providers::mqtt::{location_weather, Reading};
+        use crate::providers::units::{Celsius, Coordinates, Ratio};
+        use crate::providers::WeatherRequest;
+        use pretty_assertions::assert_eq;
+        use std::collections::BTreeMap;
+        use std::time::{Duration, Instant};
+
+        const STALE_AFTER: Duration = Duration::from_secs(60);
+
+        fn request() -> WeatherRequest<Coordinates> {
+            WeatherRequest {
+                name: "Garden".into(),
+                query: Coordinates {
+                    latitude: 52.5_f64.into(),
+                    longitude: 13.4_f64.into(),
+                },
+            }
+        }
+
+        fn reading(value: f64, age: Duration) -> Reading {
+            Reading {
+                value,
+                received_at: Instant::now().checked_sub(age).expect("Instant is valid"),
+            }
+        }
+
+        #[test]
+        fn map_fresh_readings() {
+            let readings = BTreeMap::from([
+                (Field::Temperature, reading(11.5, Duration::ZERO)),
+                (Field::RelativeHumidity, reading(81.0, Duration::ZERO)),
+            ]);
+
+            let weather =
+                location_weather(&request(), &readings, STALE_AFTER).expect("Mapping works");
+
+            assert_eq!(weather.source, "local.mqtt");
+            assert_eq!(weather.location, "Garden");
+            assert_eq!(weather.temperature, Celsius::from(11.5));
+            assert_eq!(weather.relative_humidity, Some(Ratio::Percentage(81.0)));
+        }
+
+        #[test]
+        fn ignore_stale_readings() {
+            let readings = BTreeMap::from([
+                (Field::Temperature, reading(11.5, Duration::ZERO)),
+                (
+                    Field::RelativeHumidity,
+                    reading(81.0, Duration::from_secs(120)),
+                ),
+            ]);
+
+            let weather =
+                location_weather(&request(), &readings, STALE_AFTER).expect("Mapping works");
+
+            assert_eq!(weather.relative_humidity, None);
+        }
+
+        #[test]
+        fn fail_without_recent_temperature() {
+            let readings =
+                BTreeMap::from([(Field::Temperature, reading(11.5, Duration::from_secs(120)))]);
+
+            let error =
+                location_weather(&request(), &readings, STALE_AFTER).expect_err("Mapping fails");
+
+            assert_eq!(error.to_string(), "No temperature available for Garden");
+        }
+    }
+
+    mod serves {
+        use crate::providers::mqtt::Mqtt;
+        use crate::providers::WeatherProvider;
+
+        #[test]
+        fn serve_locations_of_topics() {
+            let provider: Mqtt = toml::from_str(
+                r#"
+                host = "localhost"
+
+                [[topics]]
+                topic = "garden/temperature"
+                location = "Garden"
+                values = { temperature = "" }
+                "#,
+            )
+            .expect("Valid configuration");
+
+            assert!(provider.serves("Garden"), "Location is served");
+            assert!(!provider.serves("Living room"), "Location is not served");
+        }
+    }
+
+    mod subscription {
+        use crate::providers::mqtt::Mqtt;
+        use crate::providers::units::{Celsius, Coordinates, Ratio};
+        use crate::providers::{WeatherProvider, WeatherRequest};
+        use moka::sync::Cache;
+        use pretty_assertions::assert_eq;
+        use reqwest::blocking::Client;
+        use std::io::{Read, Write};
+        use std::net::{TcpListener, TcpStream};
+        use std::sync::mpsc;
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        const CONNACK: [u8; 4] = [0x20, 0x02, 0x00, 0x00];
+        const PINGRESP: [u8; 2] = [0xd0, 0x00];
+
+        /// Read an MQTT packet small enough for a single byte remaining length
+        fn read_packet(stream: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+            let mut header = [0_u8; 2];
+            stream.read_exact(&mut header).ok()?;
+            let [packet_type, length] = header;
+            assert!(length < 0x80, "Test packets are small");
+            let mut body = vec![0_u8; usize::from(length)];
+            stream.read_exact(&mut body).ok()?;
+            Some((packet_type, body))
+        }
+
+        fn publish_packet(topic: &str, payload: &str) -> Vec<u8> {
+            let mut body = vec![0x00, u8::try_from(topic.len()).expect("Topic is short")];
+            body.extend(topic.as_bytes());
+            body.extend(payload.as_bytes());
+
+            let mut packet = vec![0x30, u8::try_from(body.len()).expect("Payload is short")];
+            packet.extend(body);
+            packet
+        }
+
+        /// A broker accepting a single client, sending a message once it subscribed
+        fn broker(topic: &'static str, payload: &'static str) -> (u16, mpsc::Receiver<Vec<u8>>) {
+            let listener = TcpListener::bind("127.0.0.1:0").expect("Listening works");
+            let port = listener.local_addr().expect("Address is known").port();
+            let (connect_tx, connect_rx) = mpsc::channel();
+
+            thread::spawn(move || {
+                let (mut stream, _) = listener.accept().expect("Client connects");
+
+                while let Some((packet_type, body)) = read_packet(&mut stream) {
+                    match packet_type {
+                        0x10 => {
+                            connect_tx.send(body).expect("Test is waiting");
+                            stream.write_all(&CONNACK).expect("Writing works");
+                        }
+                        0x82 => {
+                            let packet_id = body.get(..2).expect("Packet ID is present");
+                            stream
+                                .write_all(&[&[0x90, 0x03], packet_id, &[0x00]].concat())
+                                .expect("Writing works");
+                            stream
+                                .write_all(&publish_packet(topic, payload))
+                                .expect("Writing works");
+                        }
+                        0xc0 => stream.write_all(&PINGRESP).expect("Writing works"),
+                        _ => {}
+                    }
+                }
+            });
+
+            (port, connect_rx)
+        }
+
+        #[test]
+        fn receive_published_values() {
+            let (port, connects) = broker(
+                "rtl_433/Bresser-6in1/42",
+                r#"{"temperature_C": 11.5, "humidity": 81}"#,
+            );
+            let provider: Mqtt = toml::from_str(&format!(
+                r#"
+                host = "127.0.0.1"
+                port = {port}
+                username = "alice"
+                password = "secret"
+
+                [[topics]]
+                topic = "rtl_433/+/42"
+                location = "Garden"
+                values = {{ temperature = "/temperature_C", relative_humidity = "/humidity" }}
+                "#
+            ))
+            .expect("Valid configuration");
+            provider.start().expect("Subscribing works");
+            let request = WeatherRequest {
+                name: "Garden".into(),
+                query: Coordinates {
+                    latitude: 52.5_f64.into(),
+                    longitude: 13.4_f64.into(),
+                },
+            };
+
+            let deadline = Instant::now()
+                .checked_add(Duration::from_secs(5))
+                .expect("Instant is valid");
+            let weather = loop {
+                let result = provider.for_coordinates(&Client::new(), &Cache::new(1), &request);
+                if let Ok(weather) = result {
+                    break weather;
+                }
+                assert!(Instant::now() < deadline, "No message received: {result:?}");
+                thread::sleep(Duration::from_millis(50));
+            };
+
+            let connect = connects.recv().expect("Client connected");
+            assert!(
+                connect.windows(5).any(|w| w == b"alice"),
+                "Username is sent"
+            );
+            assert!(
+                connect.windows(6).any(|w| w == b"secret"),
+                "Password is sent"
+            );
+            assert_eq!(weather.temperature, Celsius::from(11.5));
+            assert_eq!(weather.relative_humidity, Some(Ratio::Percentage(81.0)));
+        }
+    }
+}
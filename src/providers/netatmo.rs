@@ -0,0 +1,744 @@
+use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, Hectopascals, KilometersPerHour, Meters,
+    Millimeters, Ratio, ToMetersPerSecond,
+};
+use crate::providers::{
+    calculate_distance, HttpRequestCache, Measurement, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::{anyhow, Context};
+use log::{debug, error};
+use reqwest::blocking::Client;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tempfile::NamedTempFile;
+
+const SOURCE_URI: &str = "com.netatmo";
+const DEFAULT_BASE_URL: &str = "https://api.netatmo.com";
+
+// Refresh access tokens a bit before they expire so that no request is sent with an expired token
+const ACCESS_TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Netatmo {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    state_file: PathBuf,
+    device_id: Option<String>,
+    #[serde(default = "default_base_url")]
+    base_url: String,
+    #[serde(flatten)]
+    cache: Configuration,
+    #[serde(skip)]
+    tokens: Mutex<Tokens>,
+}
+
+fn default_base_url() -> String {
+    DEFAULT_BASE_URL.into()
+}
+
+/// Tokens obtained at runtime, the latest refresh token is kept even if it cannot be persisted
+#[derive(Debug, Default)]
+struct Tokens {
+    access_token: Option<AccessToken>,
+    refresh_token: Option<String>,
+}
+
+#[derive(Debug)]
+struct AccessToken {
+    value: String,
+    expires_at: Instant,
+}
+
+#[derive(Deserialize, Debug)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: String,
+    expires_in: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct StationsDataResponse {
+    body: StationsData,
+}
+
+#[derive(Deserialize, Debug)]
+struct StationsData {
+    devices: Vec<Device>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Device {
+    #[serde(flatten)]
+    main: Module,
+    place: Place,
+    #[serde(default)]
+    modules: Vec<Module>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Place {
+    city: Option<String>,
+    altitude: Option<f64>,
+    // Given as longitude, latitude
+    location: (Coordinate, Coordinate),
+}
+
+impl Place {
+    fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.location.1.clone(),
+            longitude: self.location.0.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct Module {
+    #[serde(rename = "_id")]
+    id: String,
+    #[serde(rename = "module_name")]
+    name: Option<String>,
+    #[serde(rename = "type")]
+    kind: ModuleType,
+    // Missing while the module is unreachable
+    dashboard_data: Option<DashboardData>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+enum ModuleType {
+    #[serde(rename = "NAMain")]
+    Main,
+    #[serde(rename = "NAModule1")]
+    Outdoor,
+    #[serde(rename = "NAModule2")]
+    Wind,
+    #[serde(rename = "NAModule3")]
+    Rain,
+    #[serde(rename = "NAModule4")]
+    Indoor,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "PascalCase")]
+struct DashboardData {
+    temperature: Option<Celsius>,
+    humidity: Option<f64>,
+    #[serde(rename = "CO2")]
+    co2: Option<f64>,
+    noise: Option<f64>,
+    pressure: Option<Hectopascals>,
+    #[serde(rename = "sum_rain_1")]
+    rain_last_hour: Option<Millimeters>,
+    wind_strength: Option<KilometersPerHour>,
+    wind_angle: Option<Degrees>,
+    gust_strength: Option<KilometersPerHour>,
+}
+
+impl Module {
+    fn label(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.id.clone())
+    }
+
+    fn dashboard(&self, kind: ModuleType) -> Option<&DashboardData> {
+        self.dashboard_data.as_ref().filter(|_| self.kind == kind)
+    }
+}
+
+impl DashboardData {
+    fn measurements(&self) -> Vec<Measurement> {
+        [("co2", "ppm", self.co2), ("noise", "decibels", self.noise)]
+            .into_iter()
+            .filter_map(|(name, unit, value)| {
+                value.map(|v| Measurement {
+                    name: name.into(),
+                    unit: Some(unit.into()),
+                    value: v,
+                })
+            })
+            .collect()
+    }
+}
+
+fn api_url(base_url: &str, path: &[&str]) -> anyhow::Result<Url> {
+    let mut url = Url::parse(base_url)?;
+
+    url.path_segments_mut()
+        .map_err(|()| anyhow!("Base URL {base_url} cannot be used as a base"))?
+        .pop_if_empty()
+        .extend(path);
+
+    Ok(url)
+}
+
+/// Map a station to one series per module that measures temperature. Rain and wind gauges report
+/// no temperature of their own, so their readings are added to the outdoor module. Without one they
+/// are left out, as the indoor readings of the main module do not describe the weather
+fn station_weather(request: &WeatherRequest<Coordinates>, device: &Device) -> Option<Weather> {
+    let coordinates = device.place.coordinates();
+    let distance = calculate_distance(&request.query, &coordinates);
+
+    let mut series: Vec<(ModuleType, Weather)> = iter::once(&device.main)
+        .chain(&device.modules)
+        .filter(|module| {
+            matches!(
+                module.kind,
+                ModuleType::Main | ModuleType::Outdoor | ModuleType::Indoor
+            )
+        })
+        .filter_map(|module| {
+            let data = module.dashboard_data.as_ref()?;
+
+            Some((
+                module.kind,
+                Weather {
+                    location: request.name.clone(),
+                    source: SOURCE_URI.into(),
+                    module: Some(module.label()),
+                    city: device.place.city.clone(),
+                    coordinates: coordinates.clone(),
+                    distance: Some(distance.clone()),
+                    elevation: device.place.altitude.map(Meters::from),
+                    temperature: data.temperature?,
+                    relative_humidity: data.humidity.map(Ratio::Percentage),
+                    pressure: data.pressure,
+                    measurements: data.measurements(),
+                    ..Weather::default()
+                },
+            ))
+        })
+        .collect();
+
+    if let Some((_, weather)) = series
+        .iter_mut()
+        .find(|(kind, _)| *kind == ModuleType::Outdoor)
+    {
+        for module in &device.modules {
+            if let Some(wind) = module.dashboard(ModuleType::Wind) {
+                weather.wind_speed = wind.wind_strength.map(|v| v.to_meters_per_second());
+                weather.wind_gust = wind.gust_strength.map(|v| v.to_meters_per_second());
+                weather.wind_direction = wind.wind_angle;
+            }
+
+            if let Some(rain) = module.dashboard(ModuleType::Rain) {
+                weather.precipitation = rain.rain_last_hour;
+            }
+        }
+    }
+
+    let mut modules = series.into_iter().map(|(_, weather)| weather);
+
+    modules.next().map(|weather| Weather {
+        modules: modules.collect(),
+        ..weather
+    })
+}
+
+/// Replace the state file with a temporary file of the same directory, so an interrupted write does
+/// not lose the refresh token. Temporary files are only readable and writable by their owner
+fn write_state_file(path: &Path, refresh_token: &str) -> anyhow::Result<()> {
+    let directory = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+
+    let mut file = NamedTempFile::new_in(directory)?;
+    file.write_all(refresh_token.as_bytes())?;
+    file.persist(path)?;
+
+    Ok(())
+}
+
+impl Netatmo {
+    // A rotated refresh token in the state file takes precedence over the configured one
+    fn current_refresh_token(&self) -> anyhow::Result<String> {
+        match fs::read_to_string(&self.state_file) {
+            Ok(token) if !token.trim().is_empty() => Ok(token.trim().to_owned()),
+            Ok(_) => Ok(self.refresh_token.clone()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(self.refresh_token.clone()),
+            Err(e) => Err(e).with_context(|| {
+                format!("Could not read state file {}", self.state_file.display())
+            }),
+        }
+    }
+
+    fn refresh_access_token(
+        &self,
+        client: &Client,
+        tokens: &mut Tokens,
+    ) -> anyhow::Result<TokenResponse> {
+        let refresh_token = match &tokens.refresh_token {
+            Some(token) => token.clone(),
+            None => self.current_refresh_token()?,
+        };
+
+        debug!("Refreshing Netatmo access token");
+
+        let response: TokenResponse = client
+            .post(api_url(&self.base_url, &["oauth2", "token"])?)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", &refresh_token),
+                ("client_id", &self.client_id),
+                ("client_secret", &self.client_secret),
+            ])
+            .send()?
+            .error_for_status()?
+            .json()?;
+
+        // Netatmo rotates refresh tokens, the previous one stops working once a new one is issued
+        tokens.refresh_token = Some(response.refresh_token.clone());
+        if let Err(e) = write_state_file(&self.state_file, &response.refresh_token) {
+            error!(
+                "Could not write state file {}, the rotated refresh token is lost on restart: {e}",
+                self.state_file.display()
+            );
+        }
+
+        Ok(response)
+    }
+
+    fn access_token(&self, client: &Client) -> anyhow::Result<String> {
+        let mut tokens = self
+            .tokens
+            .lock()
+            .map_err(|e| anyhow!("Token lock is poisoned: {e}"))?;
+
+        if let Some(token) = tokens
+            .access_token
+            .as_ref()
+            .filter(|token| token.expires_at > Instant::now())
+        {
+            return Ok(token.value.clone());
+        }
+
+        let response = self.refresh_access_token(client, &mut tokens)?;
+        let valid_for =
+            Duration::from_secs(response.expires_in).saturating_sub(ACCESS_TOKEN_EXPIRY_MARGIN);
+
+        tokens.access_token = Some(AccessToken {
+            value: response.access_token.clone(),
+            expires_at: Instant::now()
+                .checked_add(valid_for)
+                .unwrap_or_else(Instant::now),
+        });
+        drop(tokens);
+
+        Ok(response.access_token)
+    }
+}
+
+impl RegisteredProvider for Netatmo {
+    const KEY: &'static str = "netatmo";
+}
+
+impl WeatherProvider for Netatmo {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let mut url = api_url(&self.base_url, &["api", "getstationsdata"])?;
+        url.query_pairs_mut().append_pair("get_favorites", "false");
+
+        if let Some(device_id) = &self.device_id {
+            url.query_pairs_mut().append_pair("device_id", device_id);
+        }
+
+        let token = self.access_token(client)?;
+        let response: StationsDataResponse = request_cached(
+            &HttpCacheRequest::new_json_request(SOURCE_URI, client, cache, &Method::GET, &url)
+                .with_header(
+                    AUTHORIZATION,
+                    HeaderValue::from_str(&format!("Bearer {token}"))?,
+                ),
+        )?;
+
+        // Without a configured device, the station closest to the location is used
+        let device = response
+            .body
+            .devices
+            .iter()
+            .min_by(|left, right| {
+                f64::from(calculate_distance(
+                    &request.query,
+                    &left.place.coordinates(),
+                ))
+                .total_cmp(&calculate_distance(&request.query, &right.place.coordinates()).into())
+            })
+            .ok_or_else(|| anyhow!("No Netatmo station found"))?;
+
+        station_weather(request, device)
+            .ok_or_else(|| anyhow!("No module of station {} reports data", device.main.id))
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::providers::units::Coordinates;
+    use crate::providers::WeatherRequest;
+
+    const STATIONS_DATA: &str = r#"{
+        "body": {
+            "devices": [{
+                "_id": "70:ee:50:00:00:01",
+                "type": "NAMain",
+                "module_name": "Living room",
+                "place": {"city": "Berlin", "altitude": 40, "location": [13.405, 52.52]},
+                "dashboard_data": {
+                    "time_utc": 1792317600, "Temperature": 21.5, "CO2": 612, "Humidity": 48,
+                    "Noise": 38, "Pressure": 1017.2, "AbsolutePressure": 1012.1
+                },
+                "modules": [
+                    {
+                        "_id": "02:00:00:00:00:01", "type": "NAModule1", "module_name": "Garden",
+                        "dashboard_data": {"Temperature": 9.5, "Humidity": 81}
+                    },
+                    {
+                        "_id": "05:00:00:00:00:01", "type": "NAModule3", "module_name": "Rain gauge",
+                        "dashboard_data": {"Rain": 0.1, "sum_rain_1": 0.4, "sum_rain_24": 2.1}
+                    },
+                    {
+                        "_id": "06:00:00:00:00:01", "type": "NAModule2", "module_name": "Wind gauge",
+                        "dashboard_data": {
+                            "WindStrength": 18, "WindAngle": 240, "GustStrength": 36, "GustAngle": 250
+                        }
+                    },
+                    {
+                        "_id": "03:00:00:00:00:01", "type": "NAModule4",
+                        "dashboard_data": {"Temperature": 19.5, "Humidity": 55, "CO2": 800}
+                    },
+                    {"_id": "03:00:00:00:00:02", "type": "NAModule4", "module_name": "Attic"}
+                ]
+            }]
+        },
+        "status": "ok"
+    }"#;
+
+    fn request() -> WeatherRequest<Coordinates> {
+        WeatherRequest {
+            name: "Home".into(),
+            query: Coordinates {
+                latitude: 52.52_f64.into(),
+                longitude: 13.405_f64.into(),
+            },
+        }
+    }
+
+    mod station_weather {
+        use crate::providers::netatmo::tests::{request, STATIONS_DATA};
+        use crate::providers::netatmo::{station_weather, ModuleType, StationsDataResponse};
+        use crate::providers::units::{Celsius, Degrees, Hectopascals, Millimeters, Ratio};
+        use crate::providers::Measurement;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn one_series_per_module() {
+            let response: StationsDataResponse =
+                serde_json::from_str(STATIONS_DATA).expect("Parsing works");
+            let device = response.body.devices.first().expect("Device exists");
+
+            let weather = station_weather(&request(), device).expect("Station reports data");
+
+            assert_eq!(weather.module.as_deref(), Some("Living room"));
+            assert_eq!(weather.temperature, Celsius::from(21.5));
+            assert_eq!(weather.pressure, Some(Hectopascals::from(1017.2_f64)));
+            assert_eq!(
+                weather.measurements,
+                vec![
+                    Measurement {
+                        name: "co2".into(),
+                        unit: Some("ppm".into()),
+                        value: 612.0,
+                    },
+                    Measurement {
+                        name: "noise".into(),
+                        unit: Some("decibels".into()),
+                        value: 38.0,
+                    },
+                ]
+            );
+
+            let modules: Vec<_> = weather
+                .modules
+                .iter()
+                .map(|module| module.module.as_deref())
+                .collect();
+            assert_eq!(modules, vec![Some("Garden"), Some("03:00:00:00:00:01")]);
+        }
+
+        #[test]
+        fn rain_and_wind_on_outdoor_module() {
+            let response: StationsDataResponse =
+                serde_json::from_str(STATIONS_DATA).expect("Parsing works");
+            let device = response.body.devices.first().expect("Device exists");
+
+            let weather = station_weather(&request(), device).expect("Station reports data");
+            let outdoor = weather.modules.first().expect("Outdoor module exists");
+
+            assert_eq!(outdoor.relative_humidity, Some(Ratio::Percentage(81.0)));
+            assert_eq!(outdoor.precipitation, Some(Millimeters::from(0.4_f64)));
+            assert_eq!(outdoor.wind_speed.map(f64::from), Some(5.0_f64));
+            assert_eq!(outdoor.wind_gust.map(f64::from), Some(10.0_f64));
+            assert_eq!(outdoor.wind_direction, Some(Degrees::from(240.0_f64)));
+            assert_eq!(weather.wind_speed, None);
+            assert_eq!(weather.precipitation, None);
+        }
+
+        #[test]
+        fn no_rain_and_wind_without_outdoor_module() {
+            let mut response: StationsDataResponse =
+                serde_json::from_str(STATIONS_DATA).expect("Parsing works");
+            let device = response.body.devices.first_mut().expect("Device exists");
+            device
+                .modules
+                .retain(|module| module.kind != ModuleType::Outdoor);
+
+            let weather = station_weather(&request(), device).expect("Station reports data");
+
+            assert_eq!(weather.module.as_deref(), Some("Living room"));
+            assert_eq!(weather.wind_speed, None);
+            assert_eq!(weather.precipitation, None);
+            assert!(
+                weather
+                    .modules
+                    .iter()
+                    .all(|module| module.wind_speed.is_none() && module.precipitation.is_none()),
+                "Rain and wind are left out"
+            );
+        }
+    }
+
+    // Permissions are only checked where files have a mode
+    #[cfg(unix)]
+    mod write_state_file {
+        use crate::providers::netatmo::write_state_file;
+        use pretty_assertions::assert_eq;
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        #[test]
+        fn replace_state_file_readable_by_owner_only() {
+            let directory = tempfile::tempdir().expect("Temporary directory can be created");
+            let path = directory.path().join("netatmo.token");
+            fs::write(&path, "previous").expect("State file is written");
+
+            write_state_file(&path, "rotated").expect("State file is replaced");
+
+            assert_eq!(
+                fs::read_to_string(&path).expect("State file can be read"),
+                "rotated"
+            );
+            assert_eq!(
+                fs::metadata(&path)
+                    .expect("State file exists")
+                    .permissions()
+                    .mode()
+                    & 0o777,
+                0o600
+            );
+            assert_eq!(
+                fs::read_dir(directory.path())
+                    .expect("Directory can be read")
+                    .count(),
+                1,
+                "No temporary file is left behind"
+            );
+        }
+    }
+
+    #[allow(
+        clippy::significant_drop_tightening,
+        reason = "Mock servers need to outlive the requests sent to them"
+    )]
+    mod for_coordinates {
+        use crate::providers::http_request::Configuration;
+        use crate::providers::netatmo::tests::{request, STATIONS_DATA};
+        use crate::providers::netatmo::Netatmo;
+        use crate::providers::{HttpRequestCache, WeatherProvider};
+        use mockito::{Matcher, Server};
+        use reqwest::blocking::Client;
+        use std::fs;
+        use std::path::PathBuf;
+        use std::sync::Mutex;
+        use std::time::Duration;
+        use tempfile::TempDir;
+
+        /// State file in a directory of its own, which is removed with the returned directory
+        fn state_file() -> (TempDir, PathBuf) {
+            let directory = tempfile::tempdir().expect("Temporary directory can be created");
+            let path = directory.path().join("netatmo.token");
+            (directory, path)
+        }
+
+        fn provider(base_url: String, state_file: PathBuf) -> Netatmo {
+            Netatmo {
+                client_id: "client".into(),
+                client_secret: "secret".into(),
+                refresh_token: "configured".into(),
+                state_file,
+                device_id: None,
+                base_url,
+                cache: Configuration {
+                    refresh_interval: Duration::from_secs(600),
+                },
+                tokens: Mutex::default(),
+            }
+        }
+
+        fn token_body(refresh_token: &str) -> Matcher {
+            Matcher::AllOf(vec![
+                Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+                Matcher::UrlEncoded("refresh_token".into(), refresh_token.into()),
+                Matcher::UrlEncoded("client_id".into(), "client".into()),
+                Matcher::UrlEncoded("client_secret".into(), "secret".into()),
+            ])
+        }
+
+        #[test]
+        fn refresh_token_and_fetch_stations() {
+            let mut server = Server::new();
+            let token = server
+                .mock("POST", "/oauth2/token")
+                .match_body(token_body("configured"))
+                .with_body(
+                    r#"{"access_token": "access", "refresh_token": "rotated", "expires_in": 10800}"#,
+                )
+                .expect(1)
+                .create();
+            let stations = server
+                .mock("GET", "/api/getstationsdata")
+                .match_query(Matcher::UrlEncoded("get_favorites".into(), "false".into()))
+                .match_header("authorization", "Bearer access")
+                .with_body(STATIONS_DATA)
+                .expect(2)
+                .create();
+
+            let (_directory, state_file) = state_file();
+            let netatmo = provider(server.url(), state_file.clone());
+            let client = Client::new();
+
+            for _ in 0..2_u8 {
+                let weather = netatmo
+                    .for_coordinates(&client, &HttpRequestCache::new(1), &request())
+                    .expect("Weather is fetched");
+                assert_eq!(weather.modules.len(), 2, "Both modules are exported");
+            }
+
+            token.assert();
+            stations.assert();
+            assert_eq!(
+                fs::read_to_string(&state_file).expect("State file is written"),
+                "rotated",
+                "Rotated refresh token is persisted"
+            );
+        }
+
+        #[test]
+        fn prefer_refresh_token_from_state_file() {
+            let mut server = Server::new();
+            let token = server
+                .mock("POST", "/oauth2/token")
+                .match_body(token_body("persisted"))
+                .with_body(
+                    r#"{"access_token": "access", "refresh_token": "rotated", "expires_in": 10800}"#,
+                )
+                .create();
+            server
+                .mock("GET", "/api/getstationsdata")
+                .match_query(Matcher::Any)
+                .with_body(STATIONS_DATA)
+                .create();
+
+            let (_directory, state_file) = state_file();
+            fs::write(&state_file, "persisted\n").expect("State file is written");
+
+            provider(server.url(), state_file)
+                .for_coordinates(&Client::new(), &HttpRequestCache::new(1), &request())
+                .expect("Weather is fetched");
+
+            token.assert();
+        }
+
+        #[test]
+        fn keep_rotated_token_without_state_file() {
+            let mut server = Server::new();
+            let first = server
+                .mock("POST", "/oauth2/token")
+                .match_body(token_body("configured"))
+                .with_body(
+                    r#"{"access_token": "access", "refresh_token": "rotated", "expires_in": 0}"#,
+                )
+                .expect(1)
+                .create();
+            let second = server
+                .mock("POST", "/oauth2/token")
+                .match_body(token_body("rotated"))
+                .with_body(r#"{"access_token": "access", "refresh_token": "rotated-again", "expires_in": 0}"#)
+                .expect(1)
+                .create();
+            server
+                .mock("GET", "/api/getstationsdata")
+                .match_query(Matcher::Any)
+                .with_body(STATIONS_DATA)
+                .create();
+
+            let (directory, _) = state_file();
+            let netatmo = provider(
+                server.url(),
+                directory.path().join("missing").join("netatmo.token"),
+            );
+            let client = Client::new();
+
+            for _ in 0..2_u8 {
+                netatmo
+                    .for_coordinates(&client, &HttpRequestCache::new(1), &request())
+                    .expect("Weather is fetched");
+            }
+
+            first.assert();
+            second.assert();
+        }
+
+        #[test]
+        fn rejected_refresh_token() {
+            let mut server = Server::new();
+            server
+                .mock("POST", "/oauth2/token")
+                .with_status(400)
+                .with_body(r#"{"error": "invalid_grant"}"#)
+                .create();
+
+            let (_directory, state_file) = state_file();
+
+            let result = provider(server.url(), state_file.clone()).for_coordinates(
+                &Client::new(),
+                &HttpRequestCache::new(1),
+                &request(),
+            );
+
+            assert!(result.is_err(), "Rejected refresh token fails the request");
+            assert!(!state_file.exists(), "State file is left untouched");
+        }
+    }
+}
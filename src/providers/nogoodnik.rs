@@ -1,4 +1,5 @@
 use crate::providers::http_request::{request_cached, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
 use crate::providers::units::Coordinates;
 use crate::providers::HttpRequestCache;
 use crate::providers::{Weather, WeatherProvider, WeatherRequest};
@@ -14,6 +15,10 @@ pub struct Nogoodnik {}
 
 const SOURCE_URI: &str = "local.nogoodnik";
 
+impl RegisteredProvider for Nogoodnik {
+    const KEY: &'static str = "nogoodnik";
+}
+
 impl WeatherProvider for Nogoodnik {
     fn id(&self) -> &str {
         SOURCE_URI
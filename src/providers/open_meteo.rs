@@ -1,32 +1,179 @@
+use crate::providers::fields::unit_measurement;
 use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
-use crate::providers::units::Coordinates;
-use crate::providers::units::Ratio::Percentage;
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinates, Degrees, Hectopascals, Meters, MetersPerSecond, Millimeters, Ratio,
+};
 use crate::providers::{HttpRequestCache, Weather, WeatherProvider, WeatherRequest};
+use anyhow::anyhow;
 use reqwest::blocking::Client;
 use reqwest::{Method, Url};
 use rocket::serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::iter;
 use std::time::Duration;
 
 const SOURCE_URI: &str = "com.open-meteo";
 
 const ENDPOINT_URL: &str = "https://api.open-meteo.com/v1/forecast";
 
+const TEMPERATURE: &str = "temperature_2m";
+
+/// Fields of the weather model that Open-Meteo variables can be mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Temperature,
+    RelativeHumidity,
+    DewPoint,
+    Pressure,
+    WindSpeed,
+    WindGust,
+    WindDirection,
+    CloudCover,
+    Precipitation,
+    Visibility,
+    UvIndex,
+    SunshineDuration,
+}
+
+/// Variables with a counterpart in the weather model, in the units requested by default. Any other
+/// variable is exported under its own name with the unit given in the response. Similar variables
+/// like `surface_pressure` or `rain` measure something else than the field, e.g. pressure at the
+/// elevation of the location or only part of the precipitation, so they are exported as well
+const KNOWN_VARIABLES: [(&str, Field); 12] = [
+    (TEMPERATURE, Field::Temperature),
+    ("relative_humidity_2m", Field::RelativeHumidity),
+    ("dew_point_2m", Field::DewPoint),
+    ("pressure_msl", Field::Pressure),
+    ("wind_speed_10m", Field::WindSpeed),
+    ("wind_gusts_10m", Field::WindGust),
+    ("wind_direction_10m", Field::WindDirection),
+    ("cloud_cover", Field::CloudCover),
+    ("precipitation", Field::Precipitation),
+    ("visibility", Field::Visibility),
+    ("uv_index", Field::UvIndex),
+    ("sunshine_duration", Field::SunshineDuration),
+];
+
+fn known_field(variable: &str) -> Option<Field> {
+    KNOWN_VARIABLES
+        .iter()
+        .find(|&&(name, _)| name == variable)
+        .map(|&(_, field)| field)
+}
+
+fn default_variables() -> Vec<String> {
+    vec!["relative_humidity_2m".into()]
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct OpenMeteo {
     api_key: Option<String>,
+    /// Weather models to request, e.g. `icon_d2` or `ecmwf_ifs025`. Open-Meteo picks the best
+    /// suited model for the location if empty
+    #[serde(default)]
+    models: Vec<String>,
+    /// Current weather variables to request in addition to the temperature
+    #[serde(default = "default_variables")]
+    variables: Vec<String>,
+    #[serde(skip)]
+    model: Option<String>,
     #[serde(flatten)]
     cache: Configuration,
 }
 
+impl OpenMeteo {
+    /// Split into one provider per configured model, so every model is requested and exported
+    /// separately
+    pub fn per_model(self) -> Vec<Self> {
+        if self.models.is_empty() {
+            return vec![self];
+        }
+
+        self.models
+            .iter()
+            .map(|model| Self {
+                model: Some(model.clone()),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    fn current_variables(&self) -> String {
+        iter::once(TEMPERATURE)
+            .chain(
+                self.variables
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|&variable| variable != TEMPERATURE),
+            )
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+// Besides the requested variables, `current` contains the `time` and `interval` of the values
 #[derive(Deserialize, Debug)]
 struct OpenMeteoResponse {
-    current: OpenMeteoResponseCurrent,
+    current: HashMap<String, Value>,
+    #[serde(default)]
+    current_units: HashMap<String, String>,
 }
 
-#[derive(Deserialize, Debug)]
-struct OpenMeteoResponseCurrent {
-    temperature_2m: f32,
-    relative_humidity_2m: f64,
+fn set_field(weather: &mut Weather, field: Field, value: f64) {
+    match field {
+        Field::Temperature => weather.temperature = Celsius::from_f64(value),
+        Field::RelativeHumidity => weather.relative_humidity = Some(Ratio::Percentage(value)),
+        Field::DewPoint => weather.dew_point = Some(Celsius::from_f64(value)),
+        Field::Pressure => weather.pressure = Some(Hectopascals::from(value)),
+        Field::WindSpeed => weather.wind_speed = Some(MetersPerSecond::from(value)),
+        Field::WindGust => weather.wind_gust = Some(MetersPerSecond::from(value)),
+        Field::WindDirection => weather.wind_direction = Some(Degrees::from(value)),
+        Field::CloudCover => weather.cloud_cover = Some(Ratio::Percentage(value)),
+        Field::Precipitation => weather.precipitation = Some(Millimeters::from(value)),
+        Field::Visibility => weather.visibility = Some(Meters::from(value)),
+        Field::UvIndex => weather.uv_index = Some(value),
+        Field::SunshineDuration => {
+            weather.sunshine_duration = Duration::try_from_secs_f64(value).ok();
+        }
+    }
+}
+
+impl OpenMeteoResponse {
+    fn apply(&self, weather: &mut Weather, variables: &str) -> anyhow::Result<()> {
+        let temperature = self
+            .current
+            .get(TEMPERATURE)
+            .and_then(Value::as_f64)
+            .ok_or_else(|| anyhow!("No temperature in response"))?;
+        set_field(weather, Field::Temperature, temperature);
+
+        for variable in variables.split(',').filter(|&v| v != TEMPERATURE) {
+            let Some(value) = self.current.get(variable).and_then(Value::as_f64) else {
+                continue;
+            };
+
+            match known_field(variable) {
+                Some(field) => set_field(weather, field, value),
+                None => weather.measurements.push(unit_measurement(
+                    variable.into(),
+                    self.current_units.get(variable).map(String::as_str),
+                    value,
+                )),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl RegisteredProvider for OpenMeteo {
+    const KEY: &'static str = "open_meteo";
+
+    fn expand(self) -> Vec<Self> {
+        self.per_model()
+    }
 }
 
 impl WeatherProvider for OpenMeteo {
@@ -40,15 +187,21 @@ impl WeatherProvider for OpenMeteo {
         cache: &HttpRequestCache,
         request: &WeatherRequest<Coordinates>,
     ) -> anyhow::Result<Weather> {
+        let variables = self.current_variables();
         let mut url = Url::parse_with_params(
             ENDPOINT_URL,
             &[
-                ("current", "temperature_2m,relative_humidity_2m".to_owned()),
+                ("current", variables.clone()),
+                ("wind_speed_unit", "ms".into()),
                 ("latitude", request.query.latitude.to_string()),
                 ("longitude", request.query.longitude.to_string()),
             ],
         )?;
 
+        if let Some(model) = &self.model {
+            url.query_pairs_mut().append_pair("models", model);
+        }
+
         if let Some(api_key) = &self.api_key {
             url.query_pairs_mut().append_pair("apikey", api_key);
         }
@@ -61,18 +214,111 @@ impl WeatherProvider for OpenMeteo {
             &url,
         ))?;
 
-        Ok(Weather {
+        let mut weather = Weather {
             coordinates: request.query.clone(),
             source: SOURCE_URI.into(),
+            model: self.model.clone(),
             location: request.name.clone(),
             city: None,
             distance: None,
-            temperature: response.current.temperature_2m.into(),
-            relative_humidity: Some(Percentage(response.current.relative_humidity_2m)),
-        })
+            ..Weather::default()
+        };
+        response.apply(&mut weather, &variables)?;
+
+        Ok(weather)
     }
 
     fn refresh_interval(&self) -> Duration {
         Duration::from_secs(900)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    mod variables {
+        use crate::providers::open_meteo::{OpenMeteo, OpenMeteoResponse};
+        use crate::providers::units::{Celsius, MetersPerSecond, Ratio};
+        use crate::providers::{Measurement, Weather};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn always_request_temperature() {
+            let provider: OpenMeteo =
+                toml::from_str(r#"variables = ["wind_speed_10m", "temperature_2m", "cape"]"#)
+                    .expect("Valid configuration");
+
+            assert_eq!(
+                provider.current_variables(),
+                "temperature_2m,wind_speed_10m,cape"
+            );
+        }
+
+        #[test]
+        fn map_known_and_unknown_variables() {
+            let response: OpenMeteoResponse = serde_json::from_str(
+                r#"{
+                    "latitude": 52.52, "longitude": 13.419998, "generationtime_ms": 0.05, "utc_offset_seconds": 0,
+                    "timezone": "GMT", "timezone_abbreviation": "GMT", "elevation": 38.0,
+                    "current_units": {"time": "iso8601", "interval": "seconds", "temperature_2m": "\u00b0C", "relative_humidity_2m": "%", "wind_speed_10m": "m/s", "shortwave_radiation": "W/m\u00b2", "cape": "J/kg"},
+                    "current": {"time": "2026-10-18T10:00", "interval": 900, "temperature_2m": 11.4, "relative_humidity_2m": 81, "wind_speed_10m": 4.1, "shortwave_radiation": 212.0, "cape": null}
+                }"#,
+            )
+            .expect("Parsing works");
+
+            let mut weather = Weather::default();
+            response
+                .apply(
+                    &mut weather,
+                    "temperature_2m,relative_humidity_2m,wind_speed_10m,shortwave_radiation,cape",
+                )
+                .expect("Temperature is present");
+
+            assert_eq!(weather.temperature, Celsius::from(11.4));
+            assert_eq!(weather.relative_humidity, Some(Ratio::Percentage(81.0)));
+            assert_eq!(weather.wind_speed, Some(MetersPerSecond::from(4.1_f64)));
+            assert_eq!(
+                weather.measurements,
+                vec![Measurement {
+                    name: "shortwave_radiation".into(),
+                    unit: Some("watts_per_square_meter".into()),
+                    value: 212.0,
+                }]
+            );
+        }
+    }
+
+    mod per_model {
+        use crate::providers::open_meteo::OpenMeteo;
+        use pretty_assertions::assert_eq;
+
+        fn provider(models: &str) -> OpenMeteo {
+            toml::from_str(&format!("models = [{models}]")).expect("Valid configuration")
+        }
+
+        #[test]
+        fn best_match_without_models() {
+            let providers = provider("").per_model();
+
+            assert_eq!(
+                providers
+                    .iter()
+                    .map(|p| p.model.clone())
+                    .collect::<Vec<_>>(),
+                vec![None]
+            );
+        }
+
+        #[test]
+        fn one_provider_per_model() {
+            let providers = provider(r#""icon_d2", "ecmwf_ifs025""#).per_model();
+
+            assert_eq!(
+                providers
+                    .iter()
+                    .map(|p| p.model.clone())
+                    .collect::<Vec<_>>(),
+                vec![Some("icon_d2".into()), Some("ecmwf_ifs025".into())]
+            );
+        }
+    }
+}
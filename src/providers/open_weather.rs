@@ -1,7 +1,11 @@
 use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
-use crate::providers::units::{Coordinates, Kelvin, Ratio, ToCelsius};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinates, Degrees, Hectopascals, Kelvin, Meters, MetersPerSecond, Millimeters,
+    MillimetersPerHour, Ratio, ToCelsius,
+};
 use crate::providers::{
-    calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
+    calculate_distance, Alert, Forecast, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
 };
 use reqwest::blocking::Client;
 use reqwest::{Method, Url};
@@ -9,18 +13,35 @@ use rocket::serde::Deserialize;
 use serde::Serialize;
 use std::fmt::Debug;
 use std::string::ToString;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const SOURCE_URI: &str = "org.openweathermap";
 const ENDPOINT_URL: &str = "https://api.openweathermap.org/data/2.5/weather";
+const ONE_CALL_ENDPOINT_URL: &str = "https://api.openweathermap.org/data/3.0/onecall";
+
+const SECONDS_PER_HOUR: i64 = 3600;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OpenWeather {
     api_key: String,
+    #[serde(default)]
+    api_version: ApiVersion,
+    #[serde(default)]
+    forecast_hours: usize,
     #[serde(flatten)]
     cache: Configuration,
 }
 
+/// Keys created before One Call 3.0 was introduced only work with the legacy current weather API
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ApiVersion {
+    #[default]
+    #[serde(rename = "2.5")]
+    V2_5,
+    #[serde(rename = "3.0")]
+    V3_0,
+}
+
 #[derive(Deserialize, Debug)]
 struct OpenWeatherResponseMain {
     temp: Kelvin,
@@ -34,12 +55,146 @@ struct OpenWeatherResponse {
     main: OpenWeatherResponseMain,
 }
 
-impl WeatherProvider for OpenWeather {
-    fn id(&self) -> &str {
-        SOURCE_URI
+// Requested with metric units
+#[derive(Deserialize, Debug)]
+struct OneCallResponse {
+    lat: f64,
+    lon: f64,
+    current: OneCallStep,
+    minutely: Option<Vec<OneCallMinute>>,
+    hourly: Option<Vec<OneCallStep>>,
+    #[serde(default)]
+    alerts: Vec<OneCallAlert>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OneCallStep {
+    dt: i64,
+    temp: Celsius,
+    humidity: Option<f64>,
+    dew_point: Option<Celsius>,
+    pressure: Option<Hectopascals>,
+    clouds: Option<f64>,
+    wind_speed: Option<MetersPerSecond>,
+    wind_gust: Option<MetersPerSecond>,
+    wind_deg: Option<Degrees>,
+    uvi: Option<f64>,
+    // In meters
+    visibility: Option<f64>,
+    rain: Option<OneCallPrecipitation>,
+    snow: Option<OneCallPrecipitation>,
+    #[serde(default)]
+    weather: Vec<OneCallCondition>,
+}
+
+// Rain and snow are given as an intensity in millimeters per hour
+#[derive(Deserialize, Debug)]
+struct OneCallPrecipitation {
+    #[serde(rename = "1h")]
+    one_hour: f64,
+}
+
+#[derive(Deserialize, Debug)]
+struct OneCallCondition {
+    main: String,
+}
+
+// Precipitation is given as an intensity in millimeters per hour
+#[derive(Deserialize, Debug)]
+struct OneCallMinute {
+    precipitation: f64,
+}
+
+#[derive(Deserialize, Debug)]
+struct OneCallAlert {
+    sender_name: String,
+    event: String,
+    start: i64,
+    end: i64,
+}
+
+impl OneCallStep {
+    fn precipitation_intensity(&self) -> Option<MillimetersPerHour> {
+        match (&self.rain, &self.snow) {
+            (None, None) => None,
+            (rain, snow) => Some(MillimetersPerHour::from(
+                rain.as_ref().map_or(0.0, |r| r.one_hour)
+                    + snow.as_ref().map_or(0.0, |s| s.one_hour),
+            )),
+        }
     }
 
-    fn for_coordinates(
+    fn forecast(&self, hours_ahead: u32) -> Forecast {
+        Forecast {
+            hours_ahead,
+            temperature: Some(self.temp),
+            dew_point: self.dew_point,
+            pressure: self.pressure,
+            wind_speed: self.wind_speed,
+            wind_gust: self.wind_gust,
+            wind_direction: self.wind_deg,
+            cloud_cover: self.clouds.map(Ratio::Percentage),
+            // Forecast steps are an hour apart, so the hourly intensity is the precipitation of the hour
+            precipitation: self
+                .precipitation_intensity()
+                .map(|intensity| Millimeters::from(f64::from(intensity))),
+        }
+    }
+}
+
+impl OneCallResponse {
+    /// The first hourly step is the current hour
+    fn forecast(&self, hours: usize) -> Vec<Forecast> {
+        self.hourly
+            .iter()
+            .flatten()
+            .filter(|step| step.dt > self.current.dt)
+            .take(hours)
+            .filter_map(|step| {
+                let hours_ahead = step
+                    .dt
+                    .saturating_sub(self.current.dt)
+                    .saturating_add(SECONDS_PER_HOUR.saturating_sub(1))
+                    .checked_div(SECONDS_PER_HOUR)?;
+
+                Some(step.forecast(u32::try_from(hours_ahead).ok()?))
+            })
+            .collect()
+    }
+
+    /// The first minute of the nowcast is the current minute. Without a nowcast, e.g. outside of its
+    /// coverage, the intensity of the current hour is used
+    fn precipitation_intensity(&self) -> Option<MillimetersPerHour> {
+        self.minutely
+            .iter()
+            .flatten()
+            .next()
+            .map(|minute| MillimetersPerHour::from(minute.precipitation))
+            .or_else(|| self.current.precipitation_intensity())
+    }
+
+    fn active_alerts(&self, now: i64) -> Vec<Alert> {
+        self.alerts
+            .iter()
+            .filter(|alert| alert.start <= now && now <= alert.end)
+            .map(|alert| Alert {
+                sender: alert.sender_name.clone(),
+                event: alert.event.clone(),
+            })
+            .collect()
+    }
+}
+
+// The minutely nowcast provides the current precipitation intensity, so it is always requested
+const fn one_call_exclude(forecast_hours: usize) -> &'static str {
+    match forecast_hours {
+        0 => "hourly,daily",
+        _ => "daily",
+    }
+}
+
+impl OpenWeather {
+    fn current_weather(
         &self,
         client: &Client,
         cache: &HttpRequestCache,
@@ -72,10 +227,219 @@ impl WeatherProvider for OpenWeather {
             distance: Some(distance),
             temperature: response.main.temp.to_celsius(),
             relative_humidity: Some(response.main.humidity),
+            ..Weather::default()
+        })
+    }
+
+    fn one_call(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let url = Url::parse_with_params(
+            ONE_CALL_ENDPOINT_URL,
+            &[
+                ("lat", request.query.latitude.to_string()),
+                ("lon", request.query.longitude.to_string()),
+                ("units", "metric".into()),
+                ("exclude", one_call_exclude(self.forecast_hours).into()),
+                ("appid", self.api_key.clone()),
+            ],
+        )?;
+
+        let response: OneCallResponse = request_cached(&HttpCacheRequest::new_json_request(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &url,
+        ))?;
+
+        let coordinates = Coordinates {
+            latitude: response.lat.into(),
+            longitude: response.lon.into(),
+        };
+        let distance = calculate_distance(&request.query, &coordinates);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX));
+        let current = &response.current;
+
+        Ok(Weather {
+            source: SOURCE_URI.into(),
+            location: request.name.clone(),
+            city: None,
+            coordinates,
+            distance: Some(distance),
+            temperature: current.temp,
+            relative_humidity: current.humidity.map(Ratio::Percentage),
+            dew_point: current.dew_point,
+            pressure: current.pressure,
+            wind_speed: current.wind_speed,
+            wind_gust: current.wind_gust,
+            wind_direction: current.wind_deg,
+            cloud_cover: current.clouds.map(Ratio::Percentage),
+            precipitation_intensity: response.precipitation_intensity(),
+            uv_index: current.uvi,
+            visibility: current.visibility.map(Meters::from),
+            condition: current
+                .weather
+                .first()
+                .map(|condition| condition.main.to_lowercase()),
+            forecast: response.forecast(self.forecast_hours),
+            alerts: response.active_alerts(now),
+            ..Weather::default()
         })
     }
+}
+
+impl RegisteredProvider for OpenWeather {
+    const KEY: &'static str = "open_weather";
+}
+
+impl WeatherProvider for OpenWeather {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        match self.api_version {
+            ApiVersion::V2_5 => self.current_weather(client, cache, request),
+            ApiVersion::V3_0 => self.one_call(client, cache, request),
+        }
+    }
 
     fn refresh_interval(&self) -> Duration {
         self.cache.refresh_interval
     }
 }
+
+#[cfg(test)]
+mod tests {
+    mod one_call {
+        use crate::providers::open_weather::{one_call_exclude, OneCallResponse};
+        use crate::providers::units::{Celsius, Millimeters, MillimetersPerHour};
+        use crate::providers::Alert;
+        use pretty_assertions::assert_eq;
+
+        const RESPONSE: &str = r#"{
+            "lat": 52.52, "lon": 13.405, "timezone": "Europe/Berlin", "timezone_offset": 7200,
+            "current": {"dt": 1760781600, "temp": 11.4, "feels_like": 10.6, "pressure": 1016, "humidity": 81, "dew_point": 8.2, "uvi": 1.2, "clouds": 75, "visibility": 10000, "wind_speed": 4.1, "wind_deg": 240, "wind_gust": 7.2, "weather": [{"id": 803, "main": "Clouds", "description": "broken clouds", "icon": "04d"}]},
+            "minutely": [{"dt": 1760781600, "precipitation": 3.0}, {"dt": 1760781660, "precipitation": 3.0}],
+            "hourly": [
+                {"dt": 1760781600, "temp": 11.4, "pressure": 1016, "humidity": 81, "dew_point": 8.2, "clouds": 75, "wind_speed": 4.1, "wind_deg": 240, "weather": [], "pop": 0.2},
+                {"dt": 1760785200, "temp": 12.1, "pressure": 1015, "humidity": 78, "dew_point": 8.3, "clouds": 90, "wind_speed": 4.5, "wind_deg": 245, "weather": [], "pop": 0.6, "rain": {"1h": 0.3}},
+                {"dt": 1760788800, "temp": 12.6, "pressure": 1015, "humidity": 75, "dew_point": 8.1, "clouds": 100, "wind_speed": 4.9, "wind_deg": 250, "weather": [], "pop": 0.8, "rain": {"1h": 0.5}, "snow": {"1h": 0.1}}
+            ],
+            "alerts": [
+                {"sender_name": "Deutscher Wetterdienst", "event": "wind gusts", "start": 1760778000, "end": 1760810400, "description": "", "tags": ["Wind"]},
+                {"sender_name": "Deutscher Wetterdienst", "event": "frost", "start": 1760835600, "end": 1760864400, "description": "", "tags": []}
+            ]
+        }"#;
+
+        #[test]
+        fn map_forecast() {
+            let response: OneCallResponse = serde_json::from_str(RESPONSE).expect("Parsing works");
+
+            assert_eq!(response.current.temp, Celsius::from(11.4));
+            assert_eq!(
+                response
+                    .current
+                    .weather
+                    .first()
+                    .map(|condition| condition.main.as_str()),
+                Some("Clouds")
+            );
+
+            let forecast = response.forecast(24);
+            assert_eq!(
+                forecast
+                    .iter()
+                    .map(|step| (step.hours_ahead, step.temperature))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (1, Some(Celsius::from(12.1))),
+                    (2, Some(Celsius::from(12.6)))
+                ]
+            );
+            assert_eq!(
+                forecast
+                    .iter()
+                    .map(|step| step.precipitation)
+                    .collect::<Vec<_>>(),
+                vec![
+                    Some(Millimeters::from(0.3_f64)),
+                    Some(Millimeters::from(0.6_f64))
+                ]
+            );
+        }
+
+        #[test]
+        fn map_current_conditions() {
+            let response: OneCallResponse = serde_json::from_str(RESPONSE).expect("Parsing works");
+
+            assert_eq!(response.current.uvi, Some(1.2_f64));
+            assert_eq!(response.current.visibility, Some(10_000.0_f64));
+            assert_eq!(
+                response.precipitation_intensity(),
+                Some(MillimetersPerHour::from(3.0_f64))
+            );
+        }
+
+        #[test]
+        fn map_precipitation_units() {
+            let response: OneCallResponse = serde_json::from_str(
+                r#"{
+                    "lat": 52.52, "lon": 13.405,
+                    "current": {"dt": 1760781600, "temp": 11.4, "rain": {"1h": 1.5}, "snow": {"1h": 0.5}},
+                    "hourly": [
+                        {"dt": 1760781600, "temp": 11.4},
+                        {"dt": 1760785200, "temp": 12.1, "rain": {"1h": 0.3}}
+                    ]
+                }"#,
+            )
+            .expect("Parsing works");
+
+            assert_eq!(
+                response.precipitation_intensity(),
+                Some(MillimetersPerHour::from(2.0_f64))
+            );
+            assert_eq!(
+                response
+                    .forecast(24)
+                    .iter()
+                    .map(|step| step.precipitation)
+                    .collect::<Vec<_>>(),
+                vec![Some(Millimeters::from(0.3_f64))]
+            );
+        }
+
+        #[test]
+        fn limit_forecast_hours() {
+            let response: OneCallResponse = serde_json::from_str(RESPONSE).expect("Parsing works");
+
+            assert_eq!(response.forecast(1).len(), 1);
+            assert_eq!(one_call_exclude(0), "hourly,daily");
+            assert_eq!(one_call_exclude(1), "daily");
+        }
+
+        #[test]
+        fn filter_active_alerts() {
+            let response: OneCallResponse = serde_json::from_str(RESPONSE).expect("Parsing works");
+
+            assert_eq!(
+                response.active_alerts(1_760_781_600),
+                vec![Alert {
+                    sender: "Deutscher Wetterdienst".into(),
+                    event: "wind gusts".into(),
+                }]
+            );
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinates, Degrees, Hectopascals, MetersPerSecond, Ratio,
+};
+use crate::providers::{HttpRequestCache, Weather, WeatherProvider, WeatherRequest};
+use anyhow::anyhow;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SOURCE_URI: &str = "net.pirateweather";
+const DEFAULT_BASE_URL: &str = "https://api.pirateweather.net";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PirateWeather {
+    api_key: String,
+    #[serde(default = "default_base_url")]
+    base_url: String,
+    #[serde(flatten)]
+    cache: Configuration,
+}
+
+fn default_base_url() -> String {
+    DEFAULT_BASE_URL.into()
+}
+
+#[derive(Deserialize, Debug)]
+struct PirateWeatherResponse {
+    currently: PirateWeatherResponseCurrently,
+}
+
+// Field semantics follow the Dark Sky API, requested with units=si
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct PirateWeatherResponseCurrently {
+    temperature: Celsius,
+    humidity: Option<f64>,
+    dew_point: Option<Celsius>,
+    pressure: Option<Hectopascals>,
+    wind_speed: Option<MetersPerSecond>,
+    wind_gust: Option<MetersPerSecond>,
+    wind_bearing: Option<Degrees>,
+    cloud_cover: Option<f64>,
+}
+
+fn forecast_url(base_url: &str, api_key: &str, coordinates: &Coordinates) -> anyhow::Result<Url> {
+    let mut url = Url::parse(base_url)?;
+
+    url.path_segments_mut()
+        .map_err(|()| anyhow!("Base URL {base_url} cannot be used as a base"))?
+        .pop_if_empty()
+        .extend([
+            "forecast",
+            api_key,
+            &format!("{},{}", coordinates.latitude, coordinates.longitude),
+        ]);
+
+    url.query_pairs_mut()
+        .append_pair("units", "si")
+        .append_pair("exclude", "minutely,hourly,daily,alerts,flags");
+
+    Ok(url)
+}
+
+impl RegisteredProvider for PirateWeather {
+    const KEY: &'static str = "pirate_weather";
+}
+
+impl WeatherProvider for PirateWeather {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let url = forecast_url(&self.base_url, &self.api_key, &request.query)?;
+
+        let response: PirateWeatherResponse = request_cached(&HttpCacheRequest::new_json_request(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &url,
+        ))?;
+        let currently = response.currently;
+
+        Ok(Weather {
+            location: request.name.clone(),
+            source: SOURCE_URI.into(),
+            city: None,
+            coordinates: request.query.clone(),
+            distance: None,
+            temperature: currently.temperature,
+            relative_humidity: currently.humidity.map(Ratio::Fraction),
+            dew_point: currently.dew_point,
+            pressure: currently.pressure,
+            wind_speed: currently.wind_speed,
+            wind_gust: currently.wind_gust,
+            wind_direction: currently.wind_bearing,
+            cloud_cover: currently.cloud_cover.map(Ratio::Fraction),
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod forecast_url {
+        use crate::providers::pirate_weather::{forecast_url, DEFAULT_BASE_URL};
+        use crate::providers::units::Coordinates;
+        use pretty_assertions::assert_eq;
+
+        fn coordinates() -> Coordinates {
+            Coordinates {
+                latitude: 52.520_008_f64.into(),
+                longitude: 13.404_954_f64.into(),
+            }
+        }
+
+        #[test]
+        fn default_base_url() {
+            assert_eq!(
+                forecast_url(DEFAULT_BASE_URL, "secret", &coordinates())
+                    .expect("URL is valid")
+                    .as_str(),
+                "https://api.pirateweather.net/forecast/secret/52.5200080,13.4049540?units=si&exclude=minutely%2Chourly%2Cdaily%2Calerts%2Cflags"
+            );
+        }
+
+        #[test]
+        fn self_hosted_base_url_with_path() {
+            assert_eq!(
+                forecast_url("http://localhost:8080/pirate/", "secret", &coordinates())
+                    .expect("URL is valid")
+                    .as_str(),
+                "http://localhost:8080/pirate/forecast/secret/52.5200080,13.4049540?units=si&exclude=minutely%2Chourly%2Cdaily%2Calerts%2Cflags"
+            );
+        }
+    }
+
+    mod parse_response {
+        use crate::providers::pirate_weather::PirateWeatherResponse;
+        use crate::providers::units::{Celsius, Degrees, Hectopascals, MetersPerSecond};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_currently() {
+            let response: PirateWeatherResponse = serde_json::from_str(
+                r#"{
+                    "latitude": 52.52,
+                    "longitude": 13.405,
+                    "timezone": "Europe/Berlin",
+                    "currently": {
+                        "time": 1697637600,
+                        "summary": "Cloudy",
+                        "icon": "cloudy",
+                        "temperature": 11.2,
+                        "apparentTemperature": 10.1,
+                        "dewPoint": 8.4,
+                        "humidity": 0.83,
+                        "pressure": 1012.6,
+                        "windSpeed": 4.1,
+                        "windGust": 8.9,
+                        "windBearing": 245,
+                        "cloudCover": 0.92,
+                        "uvIndex": 0.4,
+                        "visibility": 16.09
+                    }
+                }"#,
+            )
+            .expect("Parsing works");
+
+            let currently = response.currently;
+            assert_eq!(currently.temperature, Celsius::from(11.2));
+            assert_eq!(currently.humidity, Some(0.83_f64));
+            assert_eq!(currently.dew_point, Some(Celsius::from(8.4)));
+            assert_eq!(currently.pressure, Some(Hectopascals::from(1012.6_f64)));
+            assert_eq!(currently.wind_speed, Some(MetersPerSecond::from(4.1_f64)));
+            assert_eq!(currently.wind_gust, Some(MetersPerSecond::from(8.9_f64)));
+            assert_eq!(currently.wind_bearing, Some(Degrees::from(245.0_f64)));
+            assert_eq!(currently.cloud_cover, Some(0.92_f64));
+        }
+    }
+}
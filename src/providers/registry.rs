@@ -0,0 +1,166 @@
+use crate::providers::accuweather::AccuWeather;
+use crate::providers::bright_sky::BrightSky;
+use crate::providers::command::Command;
+use crate::providers::deutscher_wetterdienst::DeutscherWetterdienst;
+use crate::providers::dwd_mosmix::DwdMosmix;
+use crate::providers::eccc::Eccc;
+use crate::providers::generic_json::GenericJson;
+use crate::providers::geosphere_austria::GeosphereAustria;
+use crate::providers::home_assistant::HomeAssistant;
+use crate::providers::instances::Instances;
+use crate::providers::knmi::Knmi;
+use crate::providers::met_office::MetOffice;
+use crate::providers::meteo_swiss::MeteoSwiss;
+use crate::providers::meteoblue::Meteoblue;
+use crate::providers::mqtt::Mqtt;
+use crate::providers::netatmo::Netatmo;
+use crate::providers::nogoodnik::Nogoodnik;
+use crate::providers::open_meteo::OpenMeteo;
+use crate::providers::open_weather::OpenWeather;
+use crate::providers::pirate_weather::PirateWeather;
+use crate::providers::smhi::Smhi;
+use crate::providers::tomorrow::Tomorrow;
+use crate::providers::wunderground::Wunderground;
+use crate::providers::WeatherProvider;
+use anyhow::{anyhow, Context};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use toml::Value;
+
+pub type ConfiguredProviders = Vec<Arc<dyn WeatherProvider + Send + Sync>>;
+
+/// A provider type that is configured in its own `[provider.<KEY>]` section
+pub trait RegisteredProvider: WeatherProvider + DeserializeOwned + Send + Sync + 'static {
+    const KEY: &'static str;
+
+    /// Providers to query for a single configuration, usually just the configured one
+    fn expand(self) -> Vec<Self> {
+        vec![self]
+    }
+}
+
+/// Config key of a provider type, with the factory creating providers from its configuration
+#[derive(Debug, Clone, Copy)]
+pub struct Registration {
+    key: &'static str,
+    create: fn(Value) -> anyhow::Result<ConfiguredProviders>,
+}
+
+impl Registration {
+    const fn of<T: RegisteredProvider>() -> Self {
+        Self {
+            key: T::KEY,
+            create: create::<T>,
+        }
+    }
+}
+
+fn create<T: RegisteredProvider>(config: Value) -> anyhow::Result<ConfiguredProviders> {
+    Ok(Instances::<T>::deserialize(config)?.into_providers(T::expand))
+}
+
+/// All provider types, in the order in which they are queried
+pub const REGISTRY: [Registration; 22] = [
+    Registration::of::<OpenWeather>(),
+    Registration::of::<Meteoblue>(),
+    Registration::of::<Tomorrow>(),
+    Registration::of::<DeutscherWetterdienst>(),
+    Registration::of::<OpenMeteo>(),
+    Registration::of::<PirateWeather>(),
+    Registration::of::<AccuWeather>(),
+    Registration::of::<MeteoSwiss>(),
+    Registration::of::<Knmi>(),
+    Registration::of::<GeosphereAustria>(),
+    Registration::of::<Smhi>(),
+    Registration::of::<Eccc>(),
+    Registration::of::<MetOffice>(),
+    Registration::of::<BrightSky>(),
+    Registration::of::<DwdMosmix>(),
+    Registration::of::<Netatmo>(),
+    Registration::of::<Wunderground>(),
+    Registration::of::<Mqtt>(),
+    Registration::of::<HomeAssistant>(),
+    Registration::of::<GenericJson>(),
+    Registration::of::<Command>(),
+    Registration::of::<Nogoodnik>(),
+];
+
+/// Create the providers for the configuration sections of registered provider types
+pub fn create_providers(
+    registry: &[Registration],
+    mut configs: BTreeMap<String, Value>,
+) -> anyhow::Result<ConfiguredProviders> {
+    let mut providers = vec![];
+
+    for registration in registry {
+        if let Some(config) = configs.remove(registration.key) {
+            providers.extend((registration.create)(config).with_context(|| {
+                format!("Invalid configuration for provider {}", registration.key)
+            })?);
+        }
+    }
+
+    if let Some(key) = configs.keys().next() {
+        return Err(anyhow!("Unknown provider {key}"));
+    }
+
+    Ok(providers)
+}
+
+#[cfg(test)]
+mod tests {
+    mod create_providers {
+        use crate::providers::registry::{create_providers, REGISTRY};
+        use std::collections::{BTreeMap, HashSet};
+        use toml::Value;
+
+        fn configs(config: &str) -> BTreeMap<String, Value> {
+            toml::from_str(config).expect("Valid configuration")
+        }
+
+        #[test]
+        fn unique_keys() {
+            let keys: HashSet<_> = REGISTRY.iter().map(|r| r.key).collect();
+
+            assert_eq!(keys.len(), REGISTRY.len(), "Keys are unique");
+        }
+
+        #[test]
+        fn registry_order() {
+            let providers = create_providers(
+                &REGISTRY,
+                configs("[nogoodnik]\n[open_meteo]\n[bright_sky]"),
+            )
+            .expect("Providers are created");
+
+            let ids: Vec<_> = providers.iter().map(|p| p.id().to_owned()).collect();
+
+            assert_eq!(
+                ids,
+                vec!["com.open-meteo", "dev.brightsky", "local.nogoodnik"],
+                "Providers are created in registry order"
+            );
+        }
+
+        #[test]
+        fn unknown_provider() {
+            let error = create_providers(&REGISTRY, configs("[nogoodnik]\n[sunny]"))
+                .expect_err("Unknown providers are rejected");
+
+            assert_eq!(error.to_string(), "Unknown provider sunny");
+        }
+
+        #[test]
+        fn invalid_configuration() {
+            let error = create_providers(&REGISTRY, configs("[tomorrow]"))
+                .expect_err("Invalid configurations are rejected");
+
+            assert_eq!(
+                error.to_string(),
+                "Invalid configuration for provider tomorrow"
+            );
+        }
+    }
+}
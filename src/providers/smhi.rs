@@ -0,0 +1,364 @@
+use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::station_lookup::{find_closest_weather_station, Station};
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, Hectopascals, MetersPerSecond, Ratio,
+};
+use crate::providers::{
+    calculate_distance, HttpRequestCache, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::anyhow;
+use log::{debug, trace};
+use moka::sync::Cache;
+use once_cell::sync::OnceCell;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::Duration;
+
+const SOURCE_URI: &str = "se.smhi";
+const BASE_URL: &str = "https://opendata-download-metobs.smhi.se/api/version/1.0/parameter";
+
+const TEMPERATURE: u8 = 1;
+const WIND_DIRECTION: u8 = 3;
+const WIND_SPEED: u8 = 4;
+const RELATIVE_HUMIDITY: u8 = 6;
+const PRESSURE: u8 = 9;
+const WIND_GUST: u8 = 21;
+const DEW_POINT: u8 = 39;
+
+const PARAMETERS: [u8; 7] = [
+    TEMPERATURE,
+    RELATIVE_HUMIDITY,
+    DEW_POINT,
+    PRESSURE,
+    WIND_SPEED,
+    WIND_GUST,
+    WIND_DIRECTION,
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Smhi {
+    #[serde(default = "default_station_refresh_interval")]
+    #[serde(with = "humantime_serde")]
+    station_refresh_interval: Duration,
+    #[serde(flatten)]
+    cache: Configuration,
+    #[serde(skip)]
+    station_cache: OnceCell<HttpRequestCache>,
+}
+
+// Stations rarely come and go, so downloading the station lists once a day is plenty
+const fn default_station_refresh_interval() -> Duration {
+    Duration::from_secs(60 * 60 * 24)
+}
+
+impl Smhi {
+    /// Station lists outlive the observation cache, so they are kept separately. They do not depend
+    /// on the location, so there is one list per parameter
+    fn station_cache(&self) -> &HttpRequestCache {
+        self.station_cache.get_or_init(|| {
+            Cache::builder()
+                .max_capacity(u64::try_from(PARAMETERS.len()).unwrap_or(u64::MAX))
+                .time_to_live(self.station_refresh_interval)
+                .build()
+        })
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct SmhiStationList {
+    station: Vec<WeatherStation>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct WeatherStation {
+    id: u64,
+    name: String,
+    latitude: Coordinate,
+    longitude: Coordinate,
+    active: bool,
+}
+
+impl Station for WeatherStation {
+    fn coordinates(&self) -> Coordinates {
+        Coordinates {
+            latitude: self.latitude.clone(),
+            longitude: self.longitude.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct SmhiData {
+    value: Option<Vec<SmhiValue>>,
+}
+
+// Values are reported as strings
+#[derive(Deserialize, Debug)]
+struct SmhiValue {
+    value: String,
+}
+
+impl SmhiData {
+    fn latest(&self) -> Option<f64> {
+        self.value
+            .as_ref()
+            .and_then(|values| values.last())
+            .and_then(|value| value.value.parse().ok())
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Measurements {
+    temperature: Option<f64>,
+    relative_humidity: Option<f64>,
+    dew_point: Option<f64>,
+    pressure: Option<f64>,
+    wind_speed: Option<f64>,
+    wind_gust: Option<f64>,
+    wind_direction: Option<f64>,
+}
+
+impl Measurements {
+    fn set(&mut self, parameter: u8, value: Option<f64>) {
+        let field = match parameter {
+            TEMPERATURE => &mut self.temperature,
+            RELATIVE_HUMIDITY => &mut self.relative_humidity,
+            DEW_POINT => &mut self.dew_point,
+            PRESSURE => &mut self.pressure,
+            WIND_SPEED => &mut self.wind_speed,
+            WIND_GUST => &mut self.wind_gust,
+            WIND_DIRECTION => &mut self.wind_direction,
+            _ => return,
+        };
+
+        *field = value;
+    }
+}
+
+fn station_list(
+    client: &Client,
+    cache: &HttpRequestCache,
+    parameter: u8,
+) -> anyhow::Result<Vec<WeatherStation>> {
+    let list: SmhiStationList = request_cached(&HttpCacheRequest::new_json_request(
+        SOURCE_URI,
+        client,
+        cache,
+        &Method::GET,
+        &Url::parse(&format!("{BASE_URL}/{parameter}.json"))?,
+    ))?;
+
+    Ok(list.station)
+}
+
+fn latest_value(
+    client: &Client,
+    cache: &HttpRequestCache,
+    station_cache: &HttpRequestCache,
+    parameter: u8,
+    station: &WeatherStation,
+) -> anyhow::Result<Option<f64>> {
+    // Requesting a parameter the station does not measure fails, so check its station list first
+    let measures_parameter = station_list(client, station_cache, parameter)?
+        .iter()
+        .any(|s| s.id == station.id && s.active);
+
+    if !measures_parameter {
+        trace!("Station {} does not measure {parameter}", station.name);
+        return Ok(None);
+    }
+
+    let data: SmhiData = request_cached(&HttpCacheRequest::new_json_request(
+        SOURCE_URI,
+        client,
+        cache,
+        &Method::GET,
+        &Url::parse(&format!(
+            "{BASE_URL}/{parameter}/station/{}/period/latest-hour/data.json",
+            station.id
+        ))?,
+    ))?;
+
+    Ok(data.latest())
+}
+
+impl RegisteredProvider for Smhi {
+    const KEY: &'static str = "smhi";
+}
+
+impl WeatherProvider for Smhi {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let station_cache = self.station_cache();
+        let active_stations: Vec<WeatherStation> =
+            station_list(client, station_cache, TEMPERATURE)?
+                .into_iter()
+                .filter(|station| station.active)
+                .collect();
+
+        let closest_station = find_closest_weather_station(&request.query, &active_stations)?;
+        trace!("Found closest weather station {:?}", closest_station);
+
+        let mut measurements = Measurements::default();
+        thread::scope(|scope| {
+            let handles: Vec<_> = PARAMETERS
+                .iter()
+                .map(|&parameter| {
+                    (
+                        parameter,
+                        scope.spawn(move || {
+                            latest_value(client, cache, station_cache, parameter, closest_station)
+                        }),
+                    )
+                })
+                .collect();
+
+            for (parameter, handle) in handles {
+                let value = handle
+                    .join()
+                    .map_err(|e| anyhow!("Request for parameter {parameter} panicked: {e:?}"))?;
+
+                // Only temperature is mandatory, every other parameter is reported if available
+                match (parameter, value) {
+                    (TEMPERATURE, Err(e)) => return Err(e),
+                    (_, Err(e)) => debug!("Could not fetch parameter {parameter}: {e:?}"),
+                    (_, Ok(maybe)) => measurements.set(parameter, maybe),
+                }
+            }
+
+            Ok::<(), anyhow::Error>(())
+        })?;
+
+        let coordinates = closest_station.coordinates();
+        let distance = calculate_distance(&request.query, &coordinates);
+
+        Ok(Weather {
+            source: SOURCE_URI.into(),
+            location: request.name.clone(),
+            city: Some(closest_station.name.clone()),
+            coordinates,
+            distance: Some(distance),
+            temperature: measurements
+                .temperature
+                .map(Celsius::from_f64)
+                .ok_or_else(|| anyhow!("No temperature for station {}", closest_station.name))?,
+            relative_humidity: measurements.relative_humidity.map(Ratio::Percentage),
+            dew_point: measurements.dew_point.map(Celsius::from_f64),
+            pressure: measurements.pressure.map(Hectopascals::from),
+            wind_speed: measurements.wind_speed.map(MetersPerSecond::from),
+            wind_gust: measurements.wind_gust.map(MetersPerSecond::from),
+            wind_direction: measurements.wind_direction.map(Degrees::from),
+            ..Weather::default()
+        })
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+
+    // One data request per parameter, station lists are cached separately
+    fn cache_cardinality(&self) -> usize {
+        PARAMETERS.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod parse_response {
+        use crate::providers::smhi::{SmhiData, SmhiStationList, WeatherStation};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn parse_station_list() {
+            let list: SmhiStationList = serde_json::from_str(
+                r#"{
+                    "key": "1",
+                    "title": "Lufttemperatur: momentanv\u00e4rde, 1 g\u00e5ng/tim",
+                    "station": [
+                        {"name": "Stockholm-Observatoriekullen A", "owner": "SMHI", "ownerCategory": "CLIMATE", "measuringStations": "CORE", "id": 98230, "height": 43.133, "latitude": 59.3417, "longitude": 18.0549, "active": true, "from": -3155673600000, "to": 1760781600000, "key": "98230", "updated": 1760781600000},
+                        {"name": "Abisko", "owner": "SMHI", "ownerCategory": "CLIMATE", "measuringStations": "CORE", "id": 188800, "height": 388.0, "latitude": 68.3549, "longitude": 18.8198, "active": false, "from": -1893456000000, "to": 1230768000000, "key": "188800", "updated": 1230768000000}
+                    ]
+                }"#,
+            )
+            .expect("Parsing works");
+
+            assert_eq!(
+                list.station,
+                vec![
+                    WeatherStation {
+                        id: 98230,
+                        name: "Stockholm-Observatoriekullen A".into(),
+                        latitude: 59.3417_f64.into(),
+                        longitude: 18.0549_f64.into(),
+                        active: true,
+                    },
+                    WeatherStation {
+                        id: 188_800,
+                        name: "Abisko".into(),
+                        latitude: 68.3549_f64.into(),
+                        longitude: 18.8198_f64.into(),
+                        active: false,
+                    }
+                ]
+            );
+        }
+
+        #[test]
+        fn parse_latest_hour_data() {
+            let data: SmhiData = serde_json::from_str(
+                r#"{
+                    "value": [{"date": 1760781600000, "value": "8.4", "quality": "G"}],
+                    "updated": 1760781600000,
+                    "parameter": {"key": "1", "name": "Lufttemperatur", "unit": "degree celsius"},
+                    "station": {"key": "98230", "name": "Stockholm-Observatoriekullen A", "owner": "SMHI", "height": 43.133}
+                }"#,
+            )
+            .expect("Parsing works");
+
+            assert_eq!(data.latest(), Some(8.4_f64));
+        }
+
+        #[test]
+        fn parse_missing_data() {
+            let data: SmhiData =
+                serde_json::from_str(r#"{"value": null, "updated": 1760781600000}"#)
+                    .expect("Parsing works");
+
+            assert_eq!(data.latest(), None);
+        }
+    }
+
+    mod station_cache {
+        use crate::providers::smhi::{Smhi, PARAMETERS};
+        use crate::providers::WeatherProvider;
+        use pretty_assertions::assert_eq;
+        use std::time::Duration;
+
+        #[test]
+        fn one_station_list_per_parameter() {
+            let provider: Smhi = toml::from_str("").expect("Valid configuration");
+            let station_cache = provider.station_cache();
+
+            assert_eq!(
+                station_cache.policy().max_capacity(),
+                u64::try_from(PARAMETERS.len()).ok()
+            );
+            assert_eq!(
+                station_cache.policy().time_to_live(),
+                Some(Duration::from_secs(60 * 60 * 24))
+            );
+            assert_eq!(provider.cache_cardinality(), PARAMETERS.len());
+        }
+    }
+}
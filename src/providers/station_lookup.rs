@@ -0,0 +1,158 @@
+use crate::providers::units::Coordinates;
+use anyhow::anyhow;
+use geo::{Closest, ClosestPoint, MultiPoint, Point};
+
+pub trait Station {
+    fn coordinates(&self) -> Coordinates;
+}
+
+fn to_point(coords: &Coordinates) -> Point<f64> {
+    Point::new(
+        coords.longitude.clone().into(),
+        coords.latitude.clone().into(),
+    )
+}
+
+pub fn find_closest_weather_station<'stations, S: Station>(
+    coords: &Coordinates,
+    weather_stations: &'stations [S],
+) -> anyhow::Result<&'stations S> {
+    find_closest_station_with(coords, weather_stations, |_| true)
+}
+
+/// Not every station measures everything, so only the stations matching the predicate are
+/// considered, e.g. those currently reporting a temperature
+pub fn find_closest_station_with<'stations, S: Station, P: Fn(&S) -> bool>(
+    coords: &Coordinates,
+    weather_stations: &'stations [S],
+    predicate: P,
+) -> anyhow::Result<&'stations S> {
+    let candidates: Vec<&S> = weather_stations
+        .iter()
+        .filter(|station| predicate(station))
+        .collect();
+
+    let point = to_point(coords);
+    let points = MultiPoint::new(
+        candidates
+            .iter()
+            .map(|s| to_point(&s.coordinates()))
+            .collect(),
+    );
+
+    match points.closest_point(&point) {
+        Closest::SinglePoint(closest_point) | Closest::Intersection(closest_point) => {
+            let matching_station = candidates
+                .into_iter()
+                .find(|station| {
+                    let coordinates = station.coordinates();
+                    coordinates.longitude == closest_point.x().into()
+                        && coordinates.latitude == closest_point.y().into()
+                })
+                .ok_or_else(|| anyhow!("Could not find matching station"))?;
+
+            Ok(matching_station)
+        }
+        Closest::Indeterminate => Err(anyhow!("Could not find closest point")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod find_closest_weather_station {
+        use crate::providers::station_lookup::{
+            find_closest_station_with, find_closest_weather_station, Station,
+        };
+        use crate::providers::units::Coordinates;
+        use pretty_assertions::assert_eq;
+
+        #[derive(Debug, PartialEq)]
+        struct TestStation {
+            name: &'static str,
+            latitude: f64,
+            longitude: f64,
+        }
+
+        impl Station for TestStation {
+            fn coordinates(&self) -> Coordinates {
+                Coordinates {
+                    latitude: self.latitude.into(),
+                    longitude: self.longitude.into(),
+                }
+            }
+        }
+
+        #[test]
+        fn find_closest_station_to_a_coordinate() {
+            assert_eq!(
+                find_closest_weather_station(
+                    &Coordinates {
+                        latitude: 48.11591_f64.into(),
+                        longitude: 11.570_906_f64.into(),
+                    },
+                    &[
+                        TestStation {
+                            name: "M\u{fc}nchen-Stadt",
+                            latitude: 48.1632_f64,
+                            longitude: 11.5429_f64,
+                        },
+                        TestStation {
+                            name: "M\u{fc}nchen-Flughafen",
+                            latitude: 48.3477_f64,
+                            longitude: 11.8134_f64,
+                        },
+                    ]
+                )
+                .expect("Should find something"),
+                &TestStation {
+                    name: "M\u{fc}nchen-Stadt",
+                    latitude: 48.1632_f64,
+                    longitude: 11.5429_f64,
+                }
+            );
+        }
+
+        #[test]
+        fn skip_stations_not_matching_predicate() {
+            assert_eq!(
+                find_closest_station_with(
+                    &Coordinates {
+                        latitude: 48.11591_f64.into(),
+                        longitude: 11.570_906_f64.into(),
+                    },
+                    &[
+                        TestStation {
+                            name: "M\u{fc}nchen-Stadt",
+                            latitude: 48.1632_f64,
+                            longitude: 11.5429_f64,
+                        },
+                        TestStation {
+                            name: "M\u{fc}nchen-Flughafen",
+                            latitude: 48.3477_f64,
+                            longitude: 11.8134_f64,
+                        },
+                    ],
+                    |station| station.name != "M\u{fc}nchen-Stadt"
+                )
+                .expect("Should find something")
+                .name,
+                "M\u{fc}nchen-Flughafen"
+            );
+        }
+
+        #[test]
+        fn fail_without_stations() {
+            assert!(
+                find_closest_weather_station::<TestStation>(
+                    &Coordinates {
+                        latitude: 48.11591_f64.into(),
+                        longitude: 11.570_906_f64.into(),
+                    },
+                    &[]
+                )
+                .is_err(),
+                "No station to be found"
+            );
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
-use crate::providers::units::{Celsius, Coordinates, Ratio};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinates, Degrees, Hectopascals, Meters, MetersPerSecond, MillimetersPerHour, Ratio,
+};
 use crate::providers::{HttpRequestCache, Weather, WeatherProvider, WeatherRequest};
 use reqwest::blocking::Client;
 use reqwest::{Method, Url};
@@ -9,13 +12,52 @@ use std::time::Duration;
 const SOURCE_URI: &str = "io.tomorrow";
 const ENDPOINT_URL: &str = "https://api.tomorrow.io/v4/weather/realtime";
 
+const METERS_PER_KILOMETER: f64 = 1000.0;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Tomorrow {
     api_key: String,
+    #[serde(default = "default_fields")]
+    fields: Vec<Field>,
     #[serde(flatten)]
     cache: Configuration,
 }
 
+/// Fields that can be exported in addition to the temperature, which is always exported
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Field {
+    Humidity,
+    DewPoint,
+    Pressure,
+    WindSpeed,
+    WindGust,
+    WindDirection,
+    CloudCover,
+    Visibility,
+    UvIndex,
+    PrecipitationIntensity,
+    WeatherCode,
+}
+
+const ALL_FIELDS: [Field; 11] = [
+    Field::Humidity,
+    Field::DewPoint,
+    Field::Pressure,
+    Field::WindSpeed,
+    Field::WindGust,
+    Field::WindDirection,
+    Field::CloudCover,
+    Field::Visibility,
+    Field::UvIndex,
+    Field::PrecipitationIntensity,
+    Field::WeatherCode,
+];
+
+fn default_fields() -> Vec<Field> {
+    ALL_FIELDS.to_vec()
+}
+
 #[derive(Deserialize, Debug)]
 struct TomorrowResponse {
     data: TomorrowData,
@@ -26,10 +68,96 @@ struct TomorrowData {
     values: TomorrowValues,
 }
 
+// Requested with metric units, visibility is given in kilometers
 #[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 struct TomorrowValues {
     temperature: Celsius,
-    humidity: Ratio,
+    humidity: Option<Ratio>,
+    dew_point: Option<Celsius>,
+    pressure_sea_level: Option<Hectopascals>,
+    wind_speed: Option<MetersPerSecond>,
+    wind_gust: Option<MetersPerSecond>,
+    wind_direction: Option<Degrees>,
+    cloud_cover: Option<f64>,
+    visibility: Option<f64>,
+    uv_index: Option<f64>,
+    precipitation_intensity: Option<MillimetersPerHour>,
+    weather_code: Option<u16>,
+}
+
+/// Describe a tomorrow.io weather code the same way as the tomorrow.io documentation does
+fn weather_condition(code: u16) -> String {
+    match code {
+        1000 => "clear".into(),
+        1100 => "mostly_clear".into(),
+        1101 => "partly_cloudy".into(),
+        1102 => "mostly_cloudy".into(),
+        1001 => "cloudy".into(),
+        2000 => "fog".into(),
+        2100 => "light_fog".into(),
+        4000 => "drizzle".into(),
+        4001 => "rain".into(),
+        4200 => "light_rain".into(),
+        4201 => "heavy_rain".into(),
+        5000 => "snow".into(),
+        5001 => "flurries".into(),
+        5100 => "light_snow".into(),
+        5101 => "heavy_snow".into(),
+        6000 => "freezing_drizzle".into(),
+        6001 => "freezing_rain".into(),
+        6200 => "light_freezing_rain".into(),
+        6201 => "heavy_freezing_rain".into(),
+        7000 => "ice_pellets".into(),
+        7101 => "heavy_ice_pellets".into(),
+        7102 => "light_ice_pellets".into(),
+        8000 => "thunderstorm".into(),
+        other => format!("unknown_{other}"),
+    }
+}
+
+impl Tomorrow {
+    fn select<T>(&self, field: Field, value: Option<T>) -> Option<T> {
+        value.filter(|_| self.fields.contains(&field))
+    }
+
+    fn weather(&self, request: &WeatherRequest<Coordinates>, values: &TomorrowValues) -> Weather {
+        Weather {
+            location: request.name.clone(),
+            source: SOURCE_URI.into(),
+            city: None,
+            coordinates: request.query.clone(),
+            distance: None,
+            temperature: values.temperature,
+            relative_humidity: self.select(Field::Humidity, values.humidity),
+            dew_point: self.select(Field::DewPoint, values.dew_point),
+            pressure: self.select(Field::Pressure, values.pressure_sea_level),
+            wind_speed: self.select(Field::WindSpeed, values.wind_speed),
+            wind_gust: self.select(Field::WindGust, values.wind_gust),
+            wind_direction: self.select(Field::WindDirection, values.wind_direction),
+            cloud_cover: self.select(Field::CloudCover, values.cloud_cover.map(Ratio::Percentage)),
+            visibility: self.select(
+                Field::Visibility,
+                values
+                    .visibility
+                    .map(|v| Meters::from(v * METERS_PER_KILOMETER)),
+            ),
+            uv_index: self.select(Field::UvIndex, values.uv_index),
+            precipitation_intensity: self.select(
+                Field::PrecipitationIntensity,
+                values.precipitation_intensity,
+            ),
+            condition: self.select(
+                Field::WeatherCode,
+                values.weather_code.map(weather_condition),
+            ),
+            ..Weather::default()
+        }
+    }
+}
+
+impl RegisteredProvider for Tomorrow {
+    const KEY: &'static str = "tomorrow";
 }
 
 impl WeatherProvider for Tomorrow {
@@ -63,18 +191,89 @@ impl WeatherProvider for Tomorrow {
             &url,
         ))?;
 
-        Ok(Weather {
-            location: request.name.clone(),
-            source: SOURCE_URI.into(),
-            city: None,
-            coordinates: request.query.clone(),
-            distance: None,
-            temperature: response.data.values.temperature,
-            relative_humidity: Some(response.data.values.humidity),
-        })
+        Ok(self.weather(request, &response.data.values))
     }
 
     fn refresh_interval(&self) -> Duration {
         self.cache.refresh_interval
     }
 }
+
+#[cfg(test)]
+mod tests {
+    mod weather {
+        use crate::providers::http_request::Configuration;
+        use crate::providers::tomorrow::{default_fields, Field, Tomorrow, TomorrowResponse};
+        use crate::providers::units::{Coordinates, Hectopascals, MillimetersPerHour, Ratio};
+        use crate::providers::WeatherRequest;
+        use pretty_assertions::assert_eq;
+        use std::time::Duration;
+
+        const RESPONSE: &str = r#"{
+            "data": {
+                "time": "2026-10-18T10:00:00Z",
+                "values": {
+                    "cloudBase": 0.6, "cloudCeiling": 0.6, "cloudCover": 90, "dewPoint": 8.2,
+                    "freezingRainIntensity": 0, "humidity": 81, "precipitationProbability": 20,
+                    "pressureSeaLevel": 1016.3, "pressureSurfaceLevel": 1010.1, "rainIntensity": 0.4,
+                    "precipitationIntensity": 0.4, "sleetIntensity": 0, "snowIntensity": 0,
+                    "temperature": 11.4, "temperatureApparent": 11.4, "uvHealthConcern": 0, "uvIndex": 1,
+                    "visibility": 16, "weatherCode": 4200, "windDirection": 240, "windGust": 7.2,
+                    "windSpeed": 4.1
+                }
+            },
+            "location": {"lat": 52.52, "lon": 13.405}
+        }"#;
+
+        fn provider(fields: Vec<Field>) -> Tomorrow {
+            Tomorrow {
+                api_key: "abcdefg".into(),
+                fields,
+                cache: Configuration {
+                    refresh_interval: Duration::from_secs(600),
+                },
+            }
+        }
+
+        fn request() -> WeatherRequest<Coordinates> {
+            WeatherRequest {
+                name: "Berlin".into(),
+                query: Coordinates {
+                    latitude: 52.52_f64.into(),
+                    longitude: 13.405_f64.into(),
+                },
+            }
+        }
+
+        #[test]
+        fn map_all_fields() {
+            let response: TomorrowResponse = serde_json::from_str(RESPONSE).expect("Parsing works");
+
+            let weather = provider(default_fields()).weather(&request(), &response.data.values);
+
+            assert_eq!(weather.relative_humidity, Some(Ratio::Percentage(81.0)));
+            assert_eq!(weather.pressure, Some(Hectopascals::from(1016.3_f64)));
+            assert_eq!(weather.cloud_cover, Some(Ratio::Percentage(90.0)));
+            assert_eq!(weather.visibility.map(f64::from), Some(16_000_f64));
+            assert_eq!(weather.uv_index, Some(1_f64));
+            assert_eq!(
+                weather.precipitation_intensity,
+                Some(MillimetersPerHour::from(0.4_f64))
+            );
+            assert_eq!(weather.condition.as_deref(), Some("light_rain"));
+        }
+
+        #[test]
+        fn restrict_fields() {
+            let response: TomorrowResponse = serde_json::from_str(RESPONSE).expect("Parsing works");
+
+            let weather =
+                provider(vec![Field::DewPoint]).weather(&request(), &response.data.values);
+
+            assert!(weather.dew_point.is_some(), "Dew point is selected");
+            assert_eq!(weather.relative_humidity, None);
+            assert_eq!(weather.pressure, None);
+            assert_eq!(weather.condition, None);
+        }
+    }
+}
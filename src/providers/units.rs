@@ -12,7 +12,7 @@ impl ToCelsius for Kelvin {
     }
 }
 
-#[derive(Deserialize, Debug, Copy, Clone, From, Into, PartialEq)]
+#[derive(Deserialize, Debug, Copy, Clone, From, Into, PartialEq, Default)]
 #[into(f64)]
 pub struct Celsius(f32);
 
@@ -27,6 +27,24 @@ impl ToCelsius for Celsius {
 #[derive(Deserialize, Debug, Copy, Clone, From, PartialEq)]
 pub struct Fahrenheit(f32);
 
+/// Measurements decoded as double precision are narrowed to the precision used for temperatures
+macro_rules! impl_from_f64 {
+    ($($temperature:ident),+) => {$(
+        impl $temperature {
+            #[allow(
+                clippy::as_conversions,
+                clippy::cast_possible_truncation,
+                reason = "Narrowing is intended"
+            )]
+            pub const fn from_f64(value: f64) -> Self {
+                Self(value as f32)
+            }
+        }
+    )+};
+}
+
+impl_from_f64!(Kelvin, Celsius, Fahrenheit);
+
 pub trait ToCelsius {
     fn to_celsius(&self) -> Celsius;
 }
@@ -56,7 +74,7 @@ impl From<Ratio> for f64 {
     }
 }
 
-#[derive(Serialize, Deserialize, From, Into, Debug, Clone, Display)]
+#[derive(Serialize, Deserialize, From, Into, Debug, Clone, Display, Default)]
 #[display("{_0:.7}")]
 pub struct Coordinate(f64);
 
@@ -66,7 +84,7 @@ impl PartialEq for Coordinate {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Coordinates {
     #[serde(alias = "lat")]
     pub latitude: Coordinate,
@@ -77,9 +95,132 @@ pub struct Coordinates {
 #[derive(Debug, Clone, From, Into)]
 pub struct Meters(f64);
 
+#[derive(Deserialize, Debug, Copy, Clone, From, Into, PartialEq)]
+pub struct Hectopascals(f64);
+
+impl ToHectopascals for Hectopascals {
+    fn to_hectopascals(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, From, PartialEq)]
+pub struct Kilopascals(f64);
+
+pub trait ToHectopascals {
+    fn to_hectopascals(&self) -> Hectopascals;
+}
+
+const KILOPASCALS_HECTOPASCALS_RATIO: f64 = 10.0;
+
+impl ToHectopascals for Kilopascals {
+    fn to_hectopascals(&self) -> Hectopascals {
+        Hectopascals(self.0 * KILOPASCALS_HECTOPASCALS_RATIO)
+    }
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, From, PartialEq)]
+pub struct Pascals(f64);
+
+const PASCALS_PER_HECTOPASCAL: f64 = 100.0;
+
+impl ToHectopascals for Pascals {
+    fn to_hectopascals(&self) -> Hectopascals {
+        Hectopascals(self.0 / PASCALS_PER_HECTOPASCAL)
+    }
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, From, PartialEq)]
+pub struct InchesOfMercury(f64);
+
+const HECTOPASCALS_PER_INCH_OF_MERCURY: f64 = 33.863_886;
+
+impl ToHectopascals for InchesOfMercury {
+    fn to_hectopascals(&self) -> Hectopascals {
+        Hectopascals(self.0 * HECTOPASCALS_PER_INCH_OF_MERCURY)
+    }
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, From, Into, PartialEq)]
+pub struct MetersPerSecond(f64);
+
+impl ToMetersPerSecond for MetersPerSecond {
+    fn to_meters_per_second(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, From, PartialEq)]
+pub struct KilometersPerHour(f64);
+
+pub trait ToMetersPerSecond {
+    fn to_meters_per_second(&self) -> MetersPerSecond;
+}
+
+const KILOMETERS_PER_HOUR_METERS_PER_SECOND_RATIO: f64 = 1.0 / 3.6;
+
+impl ToMetersPerSecond for KilometersPerHour {
+    fn to_meters_per_second(&self) -> MetersPerSecond {
+        MetersPerSecond(self.0 * KILOMETERS_PER_HOUR_METERS_PER_SECOND_RATIO)
+    }
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, From, PartialEq)]
+pub struct MilesPerHour(f64);
+
+const METERS_PER_SECOND_PER_MILE_PER_HOUR: f64 = 0.447_04;
+
+impl ToMetersPerSecond for MilesPerHour {
+    fn to_meters_per_second(&self) -> MetersPerSecond {
+        MetersPerSecond(self.0 * METERS_PER_SECOND_PER_MILE_PER_HOUR)
+    }
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, From, Into, PartialEq)]
+pub struct Degrees(f64);
+
+#[derive(Deserialize, Debug, Copy, Clone, From, Into, PartialEq)]
+pub struct Millimeters(f64);
+
+#[derive(Deserialize, Debug, Copy, Clone, From, PartialEq)]
+pub struct Inches(f64);
+
+pub trait ToMillimeters {
+    fn to_millimeters(&self) -> Millimeters;
+}
+
+const MILLIMETERS_PER_INCH: f64 = 25.4;
+
+impl ToMillimeters for Inches {
+    fn to_millimeters(&self) -> Millimeters {
+        Millimeters(self.0 * MILLIMETERS_PER_INCH)
+    }
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, From, Into, PartialEq)]
+pub struct MillimetersPerHour(f64);
+
+#[derive(Deserialize, Debug, Copy, Clone, From, PartialEq)]
+pub struct InchesPerHour(f64);
+
+pub trait ToMillimetersPerHour {
+    fn to_millimeters_per_hour(&self) -> MillimetersPerHour;
+}
+
+impl ToMillimetersPerHour for InchesPerHour {
+    fn to_millimeters_per_hour(&self) -> MillimetersPerHour {
+        MillimetersPerHour(self.0 * MILLIMETERS_PER_INCH)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::providers::units::{Celsius, Fahrenheit, Kelvin, ToCelsius};
+    use crate::providers::units::{
+        Celsius, Fahrenheit, Hectopascals, Inches, InchesOfMercury, InchesPerHour, Kelvin,
+        KilometersPerHour, Kilopascals, MetersPerSecond, MilesPerHour, Millimeters,
+        MillimetersPerHour, Pascals, ToCelsius, ToHectopascals, ToMetersPerSecond, ToMillimeters,
+        ToMillimetersPerHour,
+    };
 
     #[test]
     fn test_fahrenheit_to_celsius() {
@@ -98,4 +239,57 @@ mod test {
     fn test_celsius_to_celsius() {
         assert_eq!(Celsius(37_f32).to_celsius(), Celsius(37_f32));
     }
+
+    #[test]
+    fn test_kilometers_per_hour_to_meters_per_second() {
+        assert_eq!(
+            KilometersPerHour(36_f64).to_meters_per_second(),
+            MetersPerSecond(10_f64)
+        );
+        assert_eq!(
+            KilometersPerHour(0_f64).to_meters_per_second(),
+            MetersPerSecond(0_f64)
+        );
+    }
+
+    #[test]
+    fn test_kilopascals_to_hectopascals() {
+        assert_eq!(
+            Kilopascals(101.3_f64).to_hectopascals(),
+            Hectopascals(1013_f64)
+        );
+    }
+
+    #[test]
+    fn test_pascals_to_hectopascals() {
+        assert_eq!(
+            Pascals(101_250_f64).to_hectopascals(),
+            Hectopascals(1012.5_f64)
+        );
+    }
+
+    #[test]
+    fn test_inches_of_mercury_to_hectopascals() {
+        assert_eq!(
+            InchesOfMercury(10_f64).to_hectopascals(),
+            Hectopascals(338.63886_f64)
+        );
+    }
+
+    #[test]
+    fn test_miles_per_hour_to_meters_per_second() {
+        assert_eq!(
+            MilesPerHour(10_f64).to_meters_per_second(),
+            MetersPerSecond(4.4704_f64)
+        );
+    }
+
+    #[test]
+    fn test_inches_to_millimeters() {
+        assert_eq!(Inches(0.5_f64).to_millimeters(), Millimeters(12.7_f64));
+        assert_eq!(
+            InchesPerHour(2_f64).to_millimeters_per_hour(),
+            MillimetersPerHour(50.8_f64)
+        );
+    }
 }
@@ -0,0 +1,347 @@
+use crate::providers::http_request::{request_cached, Configuration, HttpCacheRequest};
+use crate::providers::registry::RegisteredProvider;
+use crate::providers::units::{
+    Celsius, Coordinate, Coordinates, Degrees, Hectopascals, KilometersPerHour, Meters,
+    MillimetersPerHour, Ratio, ToMetersPerSecond,
+};
+use crate::providers::{
+    calculate_distance, HttpRequestCache, Measurement, Weather, WeatherProvider, WeatherRequest,
+};
+use anyhow::anyhow;
+use moka::sync::Cache;
+use once_cell::sync::OnceCell;
+use reqwest::blocking::Client;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SOURCE_URI: &str = "com.wunderground";
+const NEAR_URL: &str = "https://api.weather.com/v3/location/near";
+const CURRENT_OBSERVATIONS_URL: &str = "https://api.weather.com/v2/pws/observations/current";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Wunderground {
+    api_key: String,
+    station_id: Option<String>,
+    #[serde(default = "default_station_refresh_interval")]
+    #[serde(with = "humantime_serde")]
+    station_refresh_interval: Duration,
+    #[serde(flatten)]
+    cache: Configuration,
+    #[serde(skip)]
+    station_cache: OnceCell<HttpRequestCache>,
+}
+
+// Stations rarely come and go, so looking up the nearest one once a day is plenty
+const fn default_station_refresh_interval() -> Duration {
+    Duration::from_secs(60 * 60 * 24)
+}
+
+#[derive(Deserialize, Debug)]
+struct NearResponse {
+    location: NearStations,
+}
+
+// Stations are given as parallel lists, ordered by distance
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct NearStations {
+    station_id: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ObservationsResponse {
+    observations: Vec<Observation>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Observation {
+    #[serde(rename = "stationID")]
+    station_id: String,
+    neighborhood: Option<String>,
+    #[serde(rename = "lat")]
+    latitude: Coordinate,
+    #[serde(rename = "lon")]
+    longitude: Coordinate,
+    humidity: Option<f64>,
+    winddir: Option<Degrees>,
+    uv: Option<f64>,
+    solar_radiation: Option<f64>,
+    metric: MetricObservation,
+}
+
+// Requested with metric units, wind speeds are given in kilometers per hour
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct MetricObservation {
+    temp: Option<Celsius>,
+    dewpt: Option<Celsius>,
+    pressure: Option<Hectopascals>,
+    wind_speed: Option<KilometersPerHour>,
+    wind_gust: Option<KilometersPerHour>,
+    precip_rate: Option<MillimetersPerHour>,
+    elev: Option<f64>,
+}
+
+fn observation_weather(
+    request: &WeatherRequest<Coordinates>,
+    observation: Observation,
+) -> anyhow::Result<Weather> {
+    let coordinates = Coordinates {
+        latitude: observation.latitude,
+        longitude: observation.longitude,
+    };
+    let distance = calculate_distance(&request.query, &coordinates);
+    let metric = observation.metric;
+
+    Ok(Weather {
+        location: request.name.clone(),
+        source: SOURCE_URI.into(),
+        city: observation.neighborhood,
+        coordinates,
+        distance: Some(distance),
+        elevation: metric.elev.map(Meters::from),
+        temperature: metric.temp.ok_or_else(|| {
+            anyhow!(
+                "Station {} does not report a temperature",
+                observation.station_id
+            )
+        })?,
+        relative_humidity: observation.humidity.map(Ratio::Percentage),
+        dew_point: metric.dewpt,
+        pressure: metric.pressure,
+        wind_speed: metric.wind_speed.map(|v| v.to_meters_per_second()),
+        wind_gust: metric.wind_gust.map(|v| v.to_meters_per_second()),
+        wind_direction: observation.winddir,
+        precipitation_intensity: metric.precip_rate,
+        uv_index: observation.uv,
+        measurements: observation
+            .solar_radiation
+            .map(|v| Measurement {
+                name: "solar_radiation".into(),
+                unit: Some("watts_per_square_meter".into()),
+                value: v,
+            })
+            .into_iter()
+            .collect(),
+        ..Weather::default()
+    })
+}
+
+impl Wunderground {
+    /// Station lookups outlive the weather cache, so they are kept separately. Both hold one entry
+    /// per configured location, so the station cache is bounded like the weather cache
+    fn station_cache(&self, cache: &HttpRequestCache) -> &HttpRequestCache {
+        self.station_cache.get_or_init(|| {
+            Cache::builder()
+                .max_capacity(cache.policy().max_capacity().unwrap_or(1))
+                .time_to_live(self.station_refresh_interval)
+                .build()
+        })
+    }
+
+    fn nearest_station(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        coordinates: &Coordinates,
+    ) -> anyhow::Result<String> {
+        let url = Url::parse_with_params(
+            NEAR_URL,
+            &[
+                (
+                    "geocode",
+                    format!("{},{}", coordinates.latitude, coordinates.longitude),
+                ),
+                ("product", "pws".into()),
+                ("format", "json".into()),
+                ("apiKey", self.api_key.clone()),
+            ],
+        )?;
+
+        let response: NearResponse = request_cached(&HttpCacheRequest::new_json_request(
+            SOURCE_URI,
+            client,
+            self.station_cache(cache),
+            &Method::GET,
+            &url,
+        ))?;
+
+        response
+            .location
+            .station_id
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No personal weather station found near {coordinates:?}"))
+    }
+}
+
+impl RegisteredProvider for Wunderground {
+    const KEY: &'static str = "wunderground";
+}
+
+impl WeatherProvider for Wunderground {
+    fn id(&self) -> &str {
+        SOURCE_URI
+    }
+
+    fn for_coordinates(
+        &self,
+        client: &Client,
+        cache: &HttpRequestCache,
+        request: &WeatherRequest<Coordinates>,
+    ) -> anyhow::Result<Weather> {
+        let station_id = match &self.station_id {
+            Some(station_id) => station_id.clone(),
+            None => self.nearest_station(client, cache, &request.query)?,
+        };
+
+        let url = Url::parse_with_params(
+            CURRENT_OBSERVATIONS_URL,
+            &[
+                ("stationId", station_id.as_str()),
+                ("format", "json"),
+                ("units", "m"),
+                ("numericPrecision", "decimal"),
+                ("apiKey", &self.api_key),
+            ],
+        )?;
+
+        let response: ObservationsResponse = request_cached(&HttpCacheRequest::new_json_request(
+            SOURCE_URI,
+            client,
+            cache,
+            &Method::GET,
+            &url,
+        ))?;
+
+        let observation = response
+            .observations
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No current observation for station {station_id}"))?;
+
+        observation_weather(request, observation)
+    }
+
+    fn refresh_interval(&self) -> Duration {
+        self.cache.refresh_interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod observation_weather {
+        use crate::providers::units::{
+            Celsius, Coordinates, Degrees, Hectopascals, MillimetersPerHour, Ratio,
+        };
+        use crate::providers::wunderground::{
+            observation_weather, NearResponse, ObservationsResponse,
+        };
+        use crate::providers::WeatherRequest;
+        use pretty_assertions::assert_eq;
+
+        const OBSERVATIONS: &str = r#"{
+            "observations": [{
+                "stationID": "IBERLI1234", "obsTimeUtc": "2026-10-18T10:00:00Z",
+                "obsTimeLocal": "2026-10-18 12:00:00", "neighborhood": "Kreuzberg",
+                "softwareType": "EasyWeatherPro_V5.1.6", "country": "DE", "solarRadiation": 120.5,
+                "lon": 13.4, "realtimeFrequency": null, "epoch": 1792317600, "lat": 52.5,
+                "uv": 1.0, "winddir": 240, "humidity": 81.0, "qcStatus": 1,
+                "metric": {
+                    "temp": 11.4, "heatIndex": 11.4, "dewpt": 8.2, "windChill": 11.4,
+                    "windSpeed": 18.0, "windGust": 36.0, "pressure": 1016.3, "precipRate": 0.4,
+                    "precipTotal": 2.1, "elev": 40.0
+                }
+            }]
+        }"#;
+
+        fn request() -> WeatherRequest<Coordinates> {
+            WeatherRequest {
+                name: "Home".into(),
+                query: Coordinates {
+                    latitude: 52.51_f64.into(),
+                    longitude: 13.4_f64.into(),
+                },
+            }
+        }
+
+        #[test]
+        fn map_observation() {
+            let response: ObservationsResponse =
+                serde_json::from_str(OBSERVATIONS).expect("Parsing works");
+            let observation = response.observations.into_iter().next().expect("Exists");
+
+            let weather = observation_weather(&request(), observation).expect("Mapping works");
+
+            assert_eq!(weather.city.as_deref(), Some("Kreuzberg"));
+            assert_eq!(
+                weather.distance.map(|d| f64::from(d).round()),
+                Some(1082.0_f64)
+            );
+            assert_eq!(weather.temperature, Celsius::from(11.4));
+            assert_eq!(weather.relative_humidity, Some(Ratio::Percentage(81.0)));
+            assert_eq!(weather.pressure, Some(Hectopascals::from(1016.3_f64)));
+            assert_eq!(weather.wind_speed.map(f64::from), Some(5.0_f64));
+            assert_eq!(weather.wind_direction, Some(Degrees::from(240.0_f64)));
+            assert_eq!(
+                weather.precipitation_intensity,
+                Some(MillimetersPerHour::from(0.4_f64))
+            );
+            assert_eq!(weather.elevation.map(f64::from), Some(40.0_f64));
+        }
+
+        #[test]
+        fn fail_without_temperature() {
+            let response: ObservationsResponse =
+                serde_json::from_str(&OBSERVATIONS.replace(r#""temp": 11.4"#, r#""temp": null"#))
+                    .expect("Parsing works");
+            let observation = response.observations.into_iter().next().expect("Exists");
+
+            let error = observation_weather(&request(), observation).expect_err("Mapping fails");
+
+            assert_eq!(
+                error.to_string(),
+                "Station IBERLI1234 does not report a temperature"
+            );
+        }
+
+        #[test]
+        fn parse_near_stations() {
+            let response: NearResponse = serde_json::from_str(
+                r#"{"location": {
+                    "stationName": ["Kreuzberg", "Mitte"], "stationId": ["IBERLI1234", "IBERLI42"],
+                    "qcStatus": [1, 1], "latitude": [52.5, 52.52], "longitude": [13.4, 13.41],
+                    "distanceKm": [1.11, 2.3], "distanceMi": [0.69, 1.43]
+                }}"#,
+            )
+            .expect("Parsing works");
+
+            assert_eq!(
+                response.location.station_id,
+                vec!["IBERLI1234".to_owned(), "IBERLI42".to_owned()]
+            );
+        }
+    }
+
+    mod station_cache {
+        use crate::providers::wunderground::Wunderground;
+        use moka::sync::Cache;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn bound_by_weather_cache() {
+            let provider: Wunderground =
+                toml::from_str(r#"api_key = "secret""#).expect("Valid configuration");
+
+            assert_eq!(
+                provider
+                    .station_cache(&Cache::new(3))
+                    .policy()
+                    .max_capacity(),
+                Some(3)
+            );
+        }
+    }
+}
@@ -0,0 +1,451 @@
+use crate::providers::units::{
+    Coordinates, Degrees, Fahrenheit, Inches, InchesOfMercury, InchesPerHour, MilesPerHour, Ratio,
+    ToCelsius, ToHectopascals, ToMetersPerSecond, ToMillimeters, ToMillimetersPerHour,
+};
+use crate::providers::{Measurement, Weather};
+use anyhow::anyhow;
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::{PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+const SOURCE_URI: &str = "local.station";
+
+// Weather Underground uploads use this value for sensors that are not connected
+const MISSING_VALUE: i16 = -9999;
+
+/// A personal weather station pushing its observations via the Ecowitt or the Weather Underground
+/// protocol
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Station {
+    name: Option<String>,
+    #[serde(flatten)]
+    coordinates: Coordinates,
+    passkey: Option<String>,
+    id: Option<String>,
+    password: Option<String>,
+    #[serde(default = "default_max_age")]
+    #[serde(with = "humantime_serde")]
+    max_age: Duration,
+}
+
+// Stations usually upload every minute, so a station that stayed silent for 10 minutes is gone
+const fn default_max_age() -> Duration {
+    Duration::from_secs(60 * 10)
+}
+
+pub type Fields = HashMap<String, String>;
+
+/// Field names of an upload protocol, values are given in imperial units
+#[derive(Debug)]
+pub struct Protocol {
+    name: &'static str,
+    authenticate: fn(&Station, &Fields) -> bool,
+    temperature: &'static str,
+    humidity: &'static str,
+    dew_point: &'static str,
+    pressure: &'static str,
+    wind_speed: &'static str,
+    wind_gust: &'static str,
+    wind_direction: &'static str,
+    precipitation: &'static str,
+    precipitation_intensity: &'static str,
+    uv_index: &'static str,
+    solar_radiation: &'static str,
+    indoor_temperature: &'static str,
+    indoor_humidity: &'static str,
+}
+
+pub const ECOWITT: Protocol = Protocol {
+    name: "Ecowitt",
+    authenticate: |station, fields| {
+        station.passkey.is_some() && station.passkey.as_ref() == fields.get("PASSKEY")
+    },
+    temperature: "tempf",
+    humidity: "humidity",
+    dew_point: "dewptf",
+    pressure: "baromrelin",
+    wind_speed: "windspeedmph",
+    wind_gust: "windgustmph",
+    wind_direction: "winddir",
+    precipitation: "hourlyrainin",
+    precipitation_intensity: "rainratein",
+    uv_index: "uv",
+    solar_radiation: "solarradiation",
+    indoor_temperature: "tempinf",
+    indoor_humidity: "humidityin",
+};
+
+pub const WUNDERGROUND: Protocol = Protocol {
+    name: "Weather Underground",
+    // Station IDs are public on Weather Underground, so they cannot authenticate uploads on their own
+    authenticate: |station, fields| {
+        station.id.is_some()
+            && station.id.as_ref() == fields.get("ID")
+            && station.password.is_some()
+            && station.password.as_ref() == fields.get("PASSWORD")
+    },
+    temperature: "tempf",
+    humidity: "humidity",
+    dew_point: "dewptf",
+    pressure: "baromin",
+    wind_speed: "windspeedmph",
+    wind_gust: "windgustmph",
+    wind_direction: "winddir",
+    precipitation: "rainin",
+    precipitation_intensity: "rainratein",
+    uv_index: "UV",
+    solar_radiation: "solarradiation",
+    indoor_temperature: "indoortempf",
+    indoor_humidity: "indoorhumidity",
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Rejection {
+    UnknownStation,
+    InvalidUpload(String),
+}
+
+fn value<T: FromStr + PartialOrd + From<i16>>(fields: &Fields, name: &str) -> Option<T> {
+    fields
+        .get(name)?
+        .parse()
+        .ok()
+        .filter(|value| *value > T::from(MISSING_VALUE))
+}
+
+fn station_weather(
+    location: &str,
+    station: &Station,
+    protocol: &Protocol,
+    fields: &Fields,
+) -> anyhow::Result<Weather> {
+    let temperature = value::<f32>(fields, protocol.temperature)
+        .ok_or_else(|| anyhow!("Upload is missing {}", protocol.temperature))?;
+
+    let indoor =
+        value::<f32>(fields, protocol.indoor_temperature).map(|indoor_temperature| Weather {
+            location: location.into(),
+            source: SOURCE_URI.into(),
+            module: Some("indoor".into()),
+            coordinates: station.coordinates.clone(),
+            temperature: Fahrenheit::from(indoor_temperature).to_celsius(),
+            relative_humidity: value(fields, protocol.indoor_humidity).map(Ratio::Percentage),
+            ..Weather::default()
+        });
+
+    let solar_radiation = value(fields, protocol.solar_radiation).map(|v| Measurement {
+        name: "solar_radiation".into(),
+        unit: Some("watts_per_square_meter".into()),
+        value: v,
+    });
+
+    Ok(Weather {
+        location: location.into(),
+        source: SOURCE_URI.into(),
+        coordinates: station.coordinates.clone(),
+        temperature: Fahrenheit::from(temperature).to_celsius(),
+        relative_humidity: value(fields, protocol.humidity).map(Ratio::Percentage),
+        dew_point: value::<f32>(fields, protocol.dew_point)
+            .map(|v| Fahrenheit::from(v).to_celsius()),
+        pressure: value::<f64>(fields, protocol.pressure)
+            .map(|v| InchesOfMercury::from(v).to_hectopascals()),
+        wind_speed: value::<f64>(fields, protocol.wind_speed)
+            .map(|v| MilesPerHour::from(v).to_meters_per_second()),
+        wind_gust: value::<f64>(fields, protocol.wind_gust)
+            .map(|v| MilesPerHour::from(v).to_meters_per_second()),
+        wind_direction: value::<f64>(fields, protocol.wind_direction).map(Degrees::from),
+        precipitation: value::<f64>(fields, protocol.precipitation)
+            .map(|v| Inches::from(v).to_millimeters()),
+        precipitation_intensity: value::<f64>(fields, protocol.precipitation_intensity)
+            .map(|v| InchesPerHour::from(v).to_millimeters_per_hour()),
+        uv_index: value(fields, protocol.uv_index),
+        measurements: solar_radiation.into_iter().collect(),
+        modules: indoor.into_iter().collect(),
+        ..Weather::default()
+    })
+}
+
+#[derive(Debug)]
+struct Upload {
+    protocol: &'static Protocol,
+    fields: Fields,
+    received_at: Instant,
+}
+
+/// Configured stations with their latest uploads
+#[derive(Debug, Default)]
+pub struct Stations {
+    stations: BTreeMap<String, Station>,
+    uploads: RwLock<BTreeMap<String, Upload>>,
+}
+
+impl Stations {
+    pub fn new(stations: BTreeMap<String, Station>) -> Self {
+        for (key, station) in &stations {
+            if station.id.is_some() && station.password.is_none() {
+                warn!(
+                    "Station {key} has no password, its Weather Underground uploads are rejected"
+                );
+            }
+        }
+
+        Self {
+            stations,
+            uploads: RwLock::default(),
+        }
+    }
+
+    pub fn receive(&self, protocol: &'static Protocol, fields: Fields) -> Result<(), Rejection> {
+        let (key, station) = self
+            .stations
+            .iter()
+            .find(|(_, station)| (protocol.authenticate)(station, &fields))
+            .ok_or(Rejection::UnknownStation)?;
+
+        station_weather(key, station, protocol, &fields)
+            .map_err(|e| Rejection::InvalidUpload(e.to_string()))?;
+
+        debug!("Received {} upload from station {key}", protocol.name);
+
+        self.uploads
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                key.clone(),
+                Upload {
+                    protocol,
+                    fields,
+                    received_at: Instant::now(),
+                },
+            );
+
+        Ok(())
+    }
+
+    /// Weather of all stations that uploaded recently enough
+    pub fn weather(&self) -> Vec<Weather> {
+        let uploads = self.uploads.read().unwrap_or_else(PoisonError::into_inner);
+
+        self.stations
+            .iter()
+            .filter_map(|(key, station)| {
+                let upload = uploads
+                    .get(key)
+                    .filter(|upload| upload.received_at.elapsed() <= station.max_age)?;
+                let location = station.name.as_deref().unwrap_or(key);
+
+                station_weather(location, station, upload.protocol, &upload.fields)
+                    .map_err(|e| error!("Could not map upload of station {key}: {e}"))
+                    .ok()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stations::{Fields, Stations};
+    use std::collections::BTreeMap;
+
+    fn stations() -> Stations {
+        Stations::new(
+            toml::from_str::<BTreeMap<_, _>>(
+                r#"
+                [garden]
+                latitude = 52.52
+                longitude = 13.405
+                passkey = "34B4F2C4A9F1B0E1"
+
+                [roof]
+                name = "Roof"
+                latitude = 52.52
+                longitude = 13.405
+                id = "KBERLIN1"
+                password = "secret"
+                "#,
+            )
+            .expect("Valid configuration"),
+        )
+    }
+
+    fn fields(fields: &[(&str, &str)]) -> Fields {
+        fields
+            .iter()
+            .map(|(name, value)| ((*name).into(), (*value).into()))
+            .collect()
+    }
+
+    mod receive {
+        use crate::stations::tests::{fields, stations};
+        use crate::stations::{Rejection, Stations, ECOWITT, WUNDERGROUND};
+        use pretty_assertions::assert_eq;
+        use std::collections::BTreeMap;
+
+        #[test]
+        fn ecowitt() {
+            let stations = stations();
+
+            let result = stations.receive(
+                &ECOWITT,
+                fields(&[("PASSKEY", "34B4F2C4A9F1B0E1"), ("tempf", "50.0")]),
+            );
+
+            assert_eq!(result, Ok(()));
+            assert_eq!(stations.weather().len(), 1, "Upload is served");
+        }
+
+        #[test]
+        fn unknown_passkey() {
+            let stations = stations();
+
+            let result = stations.receive(&ECOWITT, fields(&[("PASSKEY", "X"), ("tempf", "50")]));
+
+            assert_eq!(result, Err(Rejection::UnknownStation));
+            assert!(stations.weather().is_empty(), "Upload is not served");
+        }
+
+        #[test]
+        fn wunderground_password() {
+            let stations = stations();
+
+            let result = stations.receive(
+                &WUNDERGROUND,
+                fields(&[("ID", "KBERLIN1"), ("PASSWORD", "wrong"), ("tempf", "50")]),
+            );
+
+            assert_eq!(result, Err(Rejection::UnknownStation));
+        }
+
+        #[test]
+        fn wunderground_without_password() {
+            let stations = Stations::new(
+                toml::from_str::<BTreeMap<_, _>>(
+                    r#"
+                    [roof]
+                    latitude = 52.52
+                    longitude = 13.405
+                    id = "KBERLIN1"
+                    "#,
+                )
+                .expect("Valid configuration"),
+            );
+
+            let result = stations.receive(
+                &WUNDERGROUND,
+                fields(&[("ID", "KBERLIN1"), ("tempf", "50")]),
+            );
+
+            assert_eq!(result, Err(Rejection::UnknownStation));
+        }
+
+        #[test]
+        fn missing_temperature() {
+            let stations = stations();
+
+            let result = stations.receive(
+                &WUNDERGROUND,
+                fields(&[
+                    ("ID", "KBERLIN1"),
+                    ("PASSWORD", "secret"),
+                    ("tempf", "-9999"),
+                ]),
+            );
+
+            assert_eq!(
+                result,
+                Err(Rejection::InvalidUpload("Upload is missing tempf".into()))
+            );
+        }
+    }
+
+    mod weather {
+        use crate::providers::units::{Celsius, Degrees, Ratio};
+        use crate::stations::tests::{fields, stations};
+        use crate::stations::{ECOWITT, WUNDERGROUND};
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn convert_ecowitt_upload() {
+            let stations = stations();
+            stations
+                .receive(
+                    &ECOWITT,
+                    fields(&[
+                        ("PASSKEY", "34B4F2C4A9F1B0E1"),
+                        ("stationtype", "GW1100A_V2.1.4"),
+                        ("tempinf", "71.6"),
+                        ("humidityin", "45"),
+                        ("baromrelin", "30.000"),
+                        ("tempf", "50.0"),
+                        ("humidity", "81"),
+                        ("winddir", "240"),
+                        ("windspeedmph", "10.00"),
+                        ("windgustmph", "20.00"),
+                        ("rainratein", "0.100"),
+                        ("hourlyrainin", "0.500"),
+                        ("solarradiation", "120.5"),
+                        ("uv", "1"),
+                    ]),
+                )
+                .expect("Upload is accepted");
+
+            let weather = stations.weather().pop().expect("Weather is served");
+
+            assert_eq!(weather.location, "garden");
+            assert_eq!(weather.source, "local.station");
+            assert_eq!(weather.temperature, Celsius::from(10.0));
+            assert_eq!(weather.relative_humidity, Some(Ratio::Percentage(81.0)));
+            assert_eq!(
+                weather.pressure.map(|p| f64::from(p).round()),
+                Some(1016.0_f64)
+            );
+            assert_eq!(weather.wind_speed.map(f64::from), Some(4.4704_f64));
+            assert_eq!(weather.wind_direction, Some(Degrees::from(240.0_f64)));
+            assert_eq!(weather.precipitation.map(f64::from), Some(12.7_f64));
+            assert_eq!(
+                weather.precipitation_intensity.map(f64::from),
+                Some(2.54_f64)
+            );
+            assert_eq!(weather.uv_index, Some(1.0_f64));
+            assert_eq!(
+                weather.measurements.first().map(|m| m.value),
+                Some(120.5_f64)
+            );
+
+            let indoor = weather.modules.first().expect("Indoor module is served");
+            assert_eq!(indoor.module.as_deref(), Some("indoor"));
+            assert_eq!(indoor.temperature, Celsius::from(22.0));
+        }
+
+        #[test]
+        fn convert_wunderground_upload() {
+            let stations = stations();
+            stations
+                .receive(
+                    &WUNDERGROUND,
+                    fields(&[
+                        ("ID", "KBERLIN1"),
+                        ("PASSWORD", "secret"),
+                        ("action", "updateraw"),
+                        ("dateutc", "now"),
+                        ("tempf", "50.0"),
+                        ("dewptf", "41.0"),
+                        ("baromin", "-9999"),
+                        ("rainin", "0.5"),
+                    ]),
+                )
+                .expect("Upload is accepted");
+
+            let weather = stations.weather().pop().expect("Weather is served");
+
+            assert_eq!(weather.location, "Roof");
+            assert_eq!(weather.dew_point, Some(Celsius::from(5.0)));
+            assert_eq!(weather.pressure, None);
+            assert_eq!(weather.precipitation.map(f64::from), Some(12.7_f64));
+            assert!(weather.modules.is_empty(), "No indoor module is served");
+        }
+    }
+}